@@ -0,0 +1,353 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use nalgebra_glm::{Mat2x2, Vec2, Vec3};
+
+use crate::geometry::{DifferentiableGeometry, Geometry};
+
+/// Numbers a surface can be evaluated with. `f32` gives the point, `Jet`
+/// also gives its first and second derivatives.
+pub trait Scalar:
+    Copy
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + Add<f32, Output = Self>
+    + Sub<f32, Output = Self>
+    + Mul<f32, Output = Self>
+    + Div<f32, Output = Self>
+{
+    fn constant(value: f32) -> Self;
+    fn value(&self) -> f32;
+
+    fn recip(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn powf(self, n: f32) -> Self;
+    fn abs(self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn atan(self) -> Self;
+    fn sinh(self) -> Self;
+    fn cosh(self) -> Self;
+    fn tanh(self) -> Self;
+}
+
+impl Scalar for f32 {
+    fn constant(value: f32) -> Self {
+        value
+    }
+    fn value(&self) -> f32 {
+        *self
+    }
+
+    fn recip(self) -> Self {
+        f32::recip(self)
+    }
+    fn sqrt(self) -> Self {
+        f32::sqrt(self)
+    }
+    fn powi(self, n: i32) -> Self {
+        f32::powi(self, n)
+    }
+    fn powf(self, n: f32) -> Self {
+        f32::powf(self, n)
+    }
+    fn abs(self) -> Self {
+        f32::abs(self)
+    }
+    fn exp(self) -> Self {
+        f32::exp(self)
+    }
+    fn ln(self) -> Self {
+        f32::ln(self)
+    }
+    fn sin(self) -> Self {
+        f32::sin(self)
+    }
+    fn cos(self) -> Self {
+        f32::cos(self)
+    }
+    fn tan(self) -> Self {
+        f32::tan(self)
+    }
+    fn atan(self) -> Self {
+        f32::atan(self)
+    }
+    fn sinh(self) -> Self {
+        f32::sinh(self)
+    }
+    fn cosh(self) -> Self {
+        f32::cosh(self)
+    }
+    fn tanh(self) -> Self {
+        f32::tanh(self)
+    }
+}
+
+/// A value with its gradient and Hessian with respect to (u, v), carried
+/// through every operation by the chain and product rules.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Jet {
+    pub value: f32,
+    pub gradient: Vec2,
+    pub hessian: Mat2x2,
+}
+
+impl Jet {
+    // u and v themselves, the variables everything is differentiated by
+    pub fn variables(p: &Vec2) -> (Jet, Jet) {
+        let variable = |value, gradient| Jet { value, gradient, hessian: Mat2x2::zeros() };
+        (variable(p.x, Vec2::new(1.0, 0.0)), variable(p.y, Vec2::new(0.0, 1.0)))
+    }
+
+    // f(self) given f, f' and f'' at the value
+    fn chain(self, f: f32, df: f32, ddf: f32) -> Jet {
+        Jet {
+            value: f,
+            gradient: self.gradient * df,
+            hessian: self.hessian * df + self.gradient * self.gradient.transpose() * ddf,
+        }
+    }
+}
+
+impl Add for Jet {
+    type Output = Jet;
+    fn add(self, other: Jet) -> Jet {
+        Jet {
+            value: self.value + other.value,
+            gradient: self.gradient + other.gradient,
+            hessian: self.hessian + other.hessian,
+        }
+    }
+}
+
+impl Sub for Jet {
+    type Output = Jet;
+    fn sub(self, other: Jet) -> Jet {
+        self + -other
+    }
+}
+
+impl Mul for Jet {
+    type Output = Jet;
+    fn mul(self, other: Jet) -> Jet {
+        let cross = self.gradient * other.gradient.transpose();
+        Jet {
+            value: self.value * other.value,
+            gradient: self.gradient * other.value + other.gradient * self.value,
+            hessian: self.hessian * other.value
+                + other.hessian * self.value
+                + cross
+                + cross.transpose(),
+        }
+    }
+}
+
+impl Div for Jet {
+    type Output = Jet;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Jet) -> Jet {
+        self * other.recip()
+    }
+}
+
+impl Neg for Jet {
+    type Output = Jet;
+    fn neg(self) -> Jet {
+        Jet {
+            value: -self.value,
+            gradient: -self.gradient,
+            hessian: -self.hessian,
+        }
+    }
+}
+
+impl Add<f32> for Jet {
+    type Output = Jet;
+    fn add(self, other: f32) -> Jet {
+        Jet { value: self.value + other, ..self }
+    }
+}
+
+impl Sub<f32> for Jet {
+    type Output = Jet;
+    fn sub(self, other: f32) -> Jet {
+        Jet { value: self.value - other, ..self }
+    }
+}
+
+impl Mul<f32> for Jet {
+    type Output = Jet;
+    fn mul(self, other: f32) -> Jet {
+        Jet {
+            value: self.value * other,
+            gradient: self.gradient * other,
+            hessian: self.hessian * other,
+        }
+    }
+}
+
+impl Div<f32> for Jet {
+    type Output = Jet;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: f32) -> Jet {
+        self * other.recip()
+    }
+}
+
+impl Scalar for Jet {
+    fn constant(value: f32) -> Self {
+        Jet { value, gradient: Vec2::zeros(), hessian: Mat2x2::zeros() }
+    }
+    fn value(&self) -> f32 {
+        self.value
+    }
+
+    fn recip(self) -> Self {
+        let r = 1.0 / self.value;
+        self.chain(r, -r * r, 2.0 * r * r * r)
+    }
+    fn sqrt(self) -> Self {
+        let s = self.value.sqrt();
+        self.chain(s, 0.5 / s, -0.25 / (s * self.value))
+    }
+    fn powi(self, n: i32) -> Self {
+        // skip negative powers of zero for the vanishing derivatives
+        let (x, m) = (self.value, n as f32);
+        let df = if n == 0 { 0.0 } else { m * x.powi(n - 1) };
+        let ddf = if n == 0 || n == 1 {
+            0.0
+        } else {
+            m * (m - 1.0) * x.powi(n - 2)
+        };
+        self.chain(x.powi(n), df, ddf)
+    }
+    fn powf(self, n: f32) -> Self {
+        let x = self.value;
+        self.chain(x.powf(n), n * x.powf(n - 1.0), n * (n - 1.0) * x.powf(n - 2.0))
+    }
+    fn abs(self) -> Self {
+        // the kink at zero has no second derivative, take the side of the sign
+        let sign = if self.value < 0.0 { -1.0 } else { 1.0 };
+        self * sign
+    }
+    fn exp(self) -> Self {
+        let e = self.value.exp();
+        self.chain(e, e, e)
+    }
+    fn ln(self) -> Self {
+        let r = 1.0 / self.value;
+        self.chain(self.value.ln(), r, -r * r)
+    }
+    fn sin(self) -> Self {
+        let (sin, cos) = self.value.sin_cos();
+        self.chain(sin, cos, -sin)
+    }
+    fn cos(self) -> Self {
+        let (sin, cos) = self.value.sin_cos();
+        self.chain(cos, -sin, -cos)
+    }
+    fn tan(self) -> Self {
+        let tan = self.value.tan();
+        let sec2 = 1.0 + tan * tan;
+        self.chain(tan, sec2, 2.0 * tan * sec2)
+    }
+    fn atan(self) -> Self {
+        let r = 1.0 / (1.0 + self.value * self.value);
+        self.chain(self.value.atan(), r, -2.0 * self.value * r * r)
+    }
+    fn sinh(self) -> Self {
+        let (sinh, cosh) = (self.value.sinh(), self.value.cosh());
+        self.chain(sinh, cosh, sinh)
+    }
+    fn cosh(self) -> Self {
+        let (sinh, cosh) = (self.value.sinh(), self.value.cosh());
+        self.chain(cosh, sinh, cosh)
+    }
+    fn tanh(self) -> Self {
+        let tanh = self.value.tanh();
+        let sech2 = 1.0 - tanh * tanh;
+        self.chain(tanh, sech2, -2.0 * tanh * sech2)
+    }
+}
+
+/// A surface written once for any `Scalar`. Evaluating it with jets gives
+/// exact first and second derivatives, so an implementation only needs
+///
+/// ```ignore
+/// impl Geometry for Torus {
+///     fn evaluate(&self, p: &Vec2) -> Vec3 {
+///         autodiff::evaluate(self, p)
+///     }
+/// }
+///
+/// impl DifferentiableGeometry for Torus {
+///     fn du(&self) -> impl DifferentiableGeometry {
+///         autodiff::du(self)
+///     }
+///     fn dv(&self) -> impl DifferentiableGeometry {
+///         autodiff::dv(self)
+///     }
+/// }
+/// ```
+pub trait Parametric {
+    fn point<S: Scalar>(&self, u: S, v: S) -> [S; 3];
+}
+
+pub fn evaluate(surface: &impl Parametric, p: &Vec2) -> Vec3 {
+    let [x, y, z] = surface.point(p.x, p.y);
+    Vec3::new(x, y, z)
+}
+
+// the point with its derivatives, one jet per coordinate
+pub fn jets(surface: &impl Parametric, p: &Vec2) -> [Jet; 3] {
+    let (u, v) = Jet::variables(p);
+    surface.point(u, v)
+}
+
+/// A partial derivative of a parametric surface, `u` times by u and `v`
+/// times by v. Jets go up to second derivatives, so evaluating a third
+/// derivative panics.
+pub struct Partial<'a, G> {
+    surface: &'a G,
+    u: usize,
+    v: usize,
+}
+
+pub fn du<G: Parametric>(surface: &G) -> Partial<'_, G> {
+    Partial { surface, u: 1, v: 0 }
+}
+
+pub fn dv<G: Parametric>(surface: &G) -> Partial<'_, G> {
+    Partial { surface, u: 0, v: 1 }
+}
+
+impl<G: Parametric> Geometry for Partial<'_, G> {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        let jets = jets(self.surface, p);
+        let component = |jet: &Jet| match (self.u, self.v) {
+            (0, 0) => jet.value,
+            (1, 0) => jet.gradient.x,
+            (0, 1) => jet.gradient.y,
+            (2, 0) => jet.hessian[(0, 0)],
+            (1, 1) => jet.hessian[(0, 1)],
+            (0, 2) => jet.hessian[(1, 1)],
+            _ => panic!("automatic derivatives stop at the second order"),
+        };
+        Vec3::new(component(&jets[0]), component(&jets[1]), component(&jets[2]))
+    }
+}
+
+impl<G: Parametric> DifferentiableGeometry for Partial<'_, G> {
+    fn du(&self) -> impl DifferentiableGeometry {
+        Partial { u: self.u + 1, ..*self }
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        Partial { v: self.v + 1, ..*self }
+    }
+}
@@ -0,0 +1,219 @@
+use nalgebra_glm::Vec2;
+
+/// Cubic Bézier curve from `p0` to `p3` with control points `p1` and `p2`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CubicBezier {
+    pub p0: Vec2,
+    pub p1: Vec2,
+    pub p2: Vec2,
+    pub p3: Vec2,
+}
+
+impl CubicBezier {
+    pub fn new(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2) -> Self {
+        Self { p0, p1, p2, p3 }
+    }
+
+    // degree elevation of a quadratic curve, this is exact
+    pub fn from_quadratic(p0: Vec2, control: Vec2, p2: Vec2) -> Self {
+        let p1 = p0 + (control - p0) * (2.0 / 3.0);
+        let p2_ = p2 + (control - p2) * (2.0 / 3.0);
+        Self { p0, p1, p2: p2_, p3: p2 }
+    }
+
+    pub fn point(&self, t: f32) -> Vec2 {
+        let s = 1.0 - t;
+        self.p0 * (s * s * s)
+            + self.p1 * (3.0 * s * s * t)
+            + self.p2 * (3.0 * s * t * t)
+            + self.p3 * (t * t * t)
+    }
+
+    // de Casteljau subdivision at t
+    pub fn split(&self, t: f32) -> (CubicBezier, CubicBezier) {
+        let p01 = self.p0.lerp(&self.p1, t);
+        let p12 = self.p1.lerp(&self.p2, t);
+        let p23 = self.p2.lerp(&self.p3, t);
+        let p012 = p01.lerp(&p12, t);
+        let p123 = p12.lerp(&p23, t);
+        let p = p012.lerp(&p123, t);
+        (CubicBezier::new(self.p0, p01, p012, p), CubicBezier::new(p, p123, p23, self.p3))
+    }
+
+    // largest distance from the control points to the chord. The curve lies
+    // within the convex hull so this bounds the flattening error.
+    fn flatness(&self) -> f32 {
+        let chord = self.p3 - self.p0;
+        let length = chord.norm();
+        let distance = |p: Vec2| {
+            if length < f32::EPSILON {
+                (p - self.p0).norm()
+            } else {
+                let d = p - self.p0;
+                (d.x * chord.y - d.y * chord.x).abs() / length
+            }
+        };
+        distance(self.p1).max(distance(self.p2))
+    }
+
+    // true if the control points are within `tolerance` of the chord
+    pub fn is_straight(&self, tolerance: f32) -> bool {
+        self.flatness() <= tolerance
+    }
+
+    /// Appends points approximating the curve within `tolerance`, excluding
+    /// the start point which is assumed to be present already
+    pub fn flatten_into(&self, tolerance: f32, points: &mut Vec<Vec2>) {
+        const MAX_DEPTH: usize = 16;
+        fn recurse(curve: &CubicBezier, tolerance: f32, depth: usize, points: &mut Vec<Vec2>) {
+            if depth >= MAX_DEPTH || curve.flatness() <= tolerance {
+                points.push(curve.p3);
+                return;
+            }
+            let (a, b) = curve.split(0.5);
+            recurse(&a, tolerance, depth + 1, points);
+            recurse(&b, tolerance, depth + 1, points);
+        }
+        recurse(self, tolerance.max(1e-6), 0, points);
+    }
+}
+
+// corners turning more than this many radians split the curve fitting
+const CORNER_ANGLE: f32 = std::f32::consts::FRAC_PI_4;
+
+// a straight cubic from a to b
+fn line(a: Vec2, b: Vec2) -> CubicBezier {
+    CubicBezier::new(a, a.lerp(&b, 1.0 / 3.0), a.lerp(&b, 2.0 / 3.0), b)
+}
+
+// distance from p to the segment a b
+fn distance_to_segment(p: &Vec2, a: &Vec2, b: &Vec2) -> f32 {
+    let d = b - a;
+    let t = ((p - a).dot(&d) / d.norm_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+    (p - (a + t * d)).norm()
+}
+
+// Least squares cubic through the points at parameters `u` with the end
+// tangents fixed, only the distance of the control points is free
+fn fit_with_tangents(points: &[Vec2], u: &[f32], t1: Vec2, t2: Vec2) -> CubicBezier {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let (mut c00, mut c01, mut c11, mut x0, mut x1) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for (p, &t) in points.iter().zip(u) {
+        let s = 1.0 - t;
+        let (b0, b1, b2, b3) = (s * s * s, 3.0 * s * s * t, 3.0 * s * t * t, t * t * t);
+        let (a1, a2) = (t1 * b1, t2 * b2);
+        c00 += a1.dot(&a1);
+        c01 += a1.dot(&a2);
+        c11 += a2.dot(&a2);
+        let rest = p - (first * (b0 + b1) + last * (b2 + b3));
+        x0 += a1.dot(&rest);
+        x1 += a2.dot(&rest);
+    }
+    let det = c00 * c11 - c01 * c01;
+    let chord = (last - first).norm();
+    let (mut alpha1, mut alpha2) = match det.abs() > 1e-12 {
+        true => ((x0 * c11 - x1 * c01) / det, (c00 * x1 - c01 * x0) / det),
+        false => (0.0, 0.0),
+    };
+    // control points behind the ends make loops, fall back to a third
+    if alpha1 < 1e-6 * chord || alpha2 < 1e-6 * chord {
+        (alpha1, alpha2) = (chord / 3.0, chord / 3.0);
+    }
+    CubicBezier::new(first, first + t1 * alpha1, last + t2 * alpha2, last)
+}
+
+// largest distance of the points from the curve and where it is
+fn max_error(curve: &CubicBezier, points: &[Vec2], u: &[f32]) -> (f32, usize) {
+    points
+        .iter()
+        .zip(u)
+        .enumerate()
+        .map(|(i, (p, &t))| ((curve.point(t) - p).norm(), i))
+        .fold((0.0, points.len() / 2), |best, e| if e.0 > best.0 { e } else { best })
+}
+
+// one Newton step moving each parameter closer to the nearest curve point
+fn reparameterize(curve: &CubicBezier, points: &[Vec2], u: &mut [f32]) {
+    let d1 = [
+        (curve.p1 - curve.p0) * 3.0,
+        (curve.p2 - curve.p1) * 3.0,
+        (curve.p3 - curve.p2) * 3.0,
+    ];
+    let d2 = [(d1[1] - d1[0]) * 2.0, (d1[2] - d1[1]) * 2.0];
+    for (p, t) in points.iter().zip(u.iter_mut()) {
+        let s = 1.0 - *t;
+        let first = d1[0] * (s * s) + d1[1] * (2.0 * s * *t) + d1[2] * (*t * *t);
+        let second = d2[0] * s + d2[1] * *t;
+        let diff = curve.point(*t) - p;
+        let denominator = first.dot(&first) + diff.dot(&second);
+        if denominator.abs() > f32::EPSILON {
+            *t = (*t - diff.dot(&first) / denominator).clamp(0.0, 1.0);
+        }
+    }
+}
+
+// Schneider's algorithm, splitting where the error is largest until every
+// piece is within the tolerance
+fn fit_run(points: &[Vec2], t1: Vec2, t2: Vec2, tolerance: f32, curves: &mut Vec<CubicBezier>) {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    if points.iter().all(|p| distance_to_segment(p, &first, &last) <= tolerance) {
+        curves.push(line(first, last));
+        return;
+    }
+
+    let mut u = vec![0.0];
+    for w in points.windows(2) {
+        u.push(u.last().unwrap() + (w[1] - w[0]).norm());
+    }
+    let total = u.last().copied().unwrap_or(0.0).max(f32::EPSILON);
+    u.iter_mut().for_each(|t| *t /= total);
+
+    let mut curve = fit_with_tangents(points, &u, t1, t2);
+    let (mut error, mut split) = max_error(&curve, points, &u);
+    for _ in 0..4 {
+        if error <= tolerance || error > 4.0 * tolerance {
+            break;
+        }
+        reparameterize(&curve, points, &mut u);
+        curve = fit_with_tangents(points, &u, t1, t2);
+        (error, split) = max_error(&curve, points, &u);
+    }
+    if error <= tolerance {
+        curves.push(curve);
+        return;
+    }
+
+    let split = split.clamp(1, points.len() - 2);
+    let center = (points[split - 1] - points[split + 1]).try_normalize(f32::EPSILON);
+    let center = center.unwrap_or_else(|| (points[split - 1] - points[split]).normalize());
+    fit_run(&points[..=split], t1, center, tolerance, curves);
+    fit_run(&points[split..], -center, t2, tolerance, curves);
+}
+
+/// Approximates a polyline with cubic Bézier curves that pass within
+/// `tolerance` of every point. The polyline is split at sharp corners and
+/// straight stretches come out as straight curves.
+pub fn fit(points: &[Vec2], tolerance: f32) -> Vec<CubicBezier> {
+    let mut points = points.to_vec();
+    points.dedup();
+    let mut curves = Vec::new();
+    if points.len() < 2 {
+        return curves;
+    }
+
+    let mut start = 0;
+    for i in 1..points.len() {
+        let corner = i + 1 < points.len() && {
+            let (a, b) = (points[i] - points[i - 1], points[i + 1] - points[i]);
+            a.angle(&b) > CORNER_ANGLE
+        };
+        if corner || i + 1 == points.len() {
+            let run = &points[start..=i];
+            let t1 = (run[1] - run[0]).normalize();
+            let t2 = (run[run.len() - 2] - run[run.len() - 1]).normalize();
+            fit_run(run, t1, t2, tolerance.max(1e-6), &mut curves);
+            start = i;
+        }
+    }
+    curves
+}
@@ -8,6 +8,7 @@ use plotter::{
     camera::Camera,
     lerp::lerp,
     uv2xy::{keep_xy, reproject},
+    view_box::ViewBox,
 };
 use plotter::{geometries::hole::Hole, skia_utils::draw_polylines};
 
@@ -42,7 +43,7 @@ fn trace_field(field: &Spiral, position: &Vec2, n: usize, dt: f32) -> Polyline2
         points.push(p);
         p += field.at(&p) * dt;
     }
-    Polyline2 { points }
+    Polyline2 { points, closed: false }
 }
 
 struct Theme<'a> {
@@ -117,7 +118,7 @@ fn main() -> io::Result<()> {
                 uv_polyline,
                 &geometry,
                 &camera,
-                (0, 0, resolution.width as i32, resolution.height as i32),
+                ViewBox::size(resolution.width as f32, resolution.height as f32),
                 near,
                 far,
             );
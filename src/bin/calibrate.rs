@@ -0,0 +1,92 @@
+use std::{
+    env,
+    io::{self, ErrorKind},
+    path::PathBuf,
+};
+
+use plotter::{
+    calibration::{Calibration, DEFAULT_PATH, DEFAULT_PROFILE},
+    duration_extras::{format_duration, parse_duration},
+    svg_import::load_svg,
+};
+
+const USAGE: &str = "usage: calibrate [<drawing.svg> <time>] [-p|--profile <name>] \
+[-c|--calibration <file>] [--draw-speed <mm/min>] [--move-speed <mm/min>]";
+
+struct Args {
+    // the plotted drawing and how long it took, none to only refit
+    measurement: Option<(PathBuf, String)>,
+    profile: String,
+    calibration_path: PathBuf,
+    draw_speed: f32,
+    move_speed: f32,
+}
+
+fn invalid_input(message: impl Into<String>) -> io::Error {
+    io::Error::new(ErrorKind::InvalidInput, message.into())
+}
+
+fn parse_args() -> io::Result<Args> {
+    let mut positional = Vec::new();
+    let mut args = Args {
+        measurement: None,
+        profile: DEFAULT_PROFILE.to_string(),
+        calibration_path: PathBuf::from(DEFAULT_PATH),
+        draw_speed: 2000.0,
+        move_speed: 8000.0,
+    };
+    let mut raw = env::args().skip(1);
+    while let Some(arg) = raw.next() {
+        let mut value =
+            || raw.next().ok_or_else(|| invalid_input(format!("missing value after {arg}")));
+        let speed = |text: String| {
+            text.parse::<f32>().map_err(|_| invalid_input(format!("invalid speed: {text}")))
+        };
+        match arg.as_str() {
+            "-p" | "--profile" => args.profile = value()?,
+            "-c" | "--calibration" => args.calibration_path = PathBuf::from(value()?),
+            "--draw-speed" => args.draw_speed = speed(value()?)?,
+            "--move-speed" => args.move_speed = speed(value()?)?,
+            _ if arg.starts_with('-') => return Err(invalid_input(USAGE)),
+            _ => positional.push(arg),
+        }
+    }
+    args.measurement = match <[String; 2]>::try_from(positional) {
+        Ok([svg, time]) => Some((PathBuf::from(svg), time)),
+        Err(positional) if positional.is_empty() => None,
+        Err(_) => return Err(invalid_input(USAGE)),
+    };
+    Ok(args)
+}
+
+fn main() -> io::Result<()> {
+    let args = parse_args()?;
+    let mut calibration = Calibration::load_or_default(&args.calibration_path)?;
+
+    if let Some((svg_path, time)) = &args.measurement {
+        let duration =
+            parse_duration(time).ok_or_else(|| invalid_input(format!("invalid time: {time}")))?;
+        let paper = load_svg(svg_path, 0.1)?.into_paper(0.5);
+        let profile = calibration.profile_mut(&args.profile);
+        profile.add_paper(&paper, args.draw_speed, args.move_speed, duration);
+        calibration.save(&args.calibration_path)?;
+        println!("wrote: {}", args.calibration_path.display());
+    }
+
+    let Some(profile) = calibration.profile(&args.profile) else {
+        return Err(invalid_input(format!("no measurements for profile `{}`", args.profile)));
+    };
+    let fit = profile.fit();
+    let e = fit.estimator;
+    println!("profile: {} ({} measurements)", args.profile, profile.plots.len());
+    println!(
+        "acceleration: {} mm/s², junction deviation: {} mm, pen lift: {:.3} s",
+        e.acceleration, e.junction_deviation, e.pen_lift
+    );
+    println!("draw factor: {:.3}, move factor: {:.3}", e.c_draw, e.c_move);
+    for (m, residual) in profile.plots.iter().zip(&fit.residuals) {
+        println!("  measured {:>10}  residual {residual:+.1} s", format_duration(m.duration));
+    }
+    println!("rms residual: {:.1} s", fit.rms());
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use std::{f32::consts::TAU, io, time::Duration};
 
-use nalgebra_glm::{look_at, perspective, Vec2, Vec3, Vec4};
+use nalgebra_glm::{look_at, perspective, Vec2, Vec3};
 use plotter::{
+    calibration::{Calibration, DEFAULT_PATH, DEFAULT_PROFILE},
     camera::Camera,
     duration_extras::format_duration,
     fields::cross2,
@@ -9,10 +10,11 @@ use plotter::{
     geometry::DifferentiableGeometry,
     gridlines::generate_grid,
     integrate::euler,
-    paper::{pad, viewbox_aspect, Paper, ViewBox, A4_LANDSCAPE},
+    paper::Paper,
     polyline::Polyline2,
-    time_estimator::Estimator,
+    preview::{save_preview, PreviewOptions},
     uv2xy::{keep_xy, reproject},
+    view_box::ViewBox,
 };
 use rand::{Rng, SeedableRng};
 use rand_distr::{Distribution, Normal};
@@ -50,8 +52,8 @@ fn setup_torus(
 
     let near = 0.1;
     let far = 4.0;
-    let projection = perspective(viewbox_aspect(view_box), 45.0_f32.to_radians(), near, far);
-    let viewport = Vec4::new(area.0 as f32, area.1 as f32, area.2 as f32, area.3 as f32);
+    let projection = perspective(view_box.aspect(), 45.0_f32.to_radians(), near, far);
+    let viewport = area.viewport();
     let camera = Camera { projection, model, viewport };
 
     let uv_polylines = generate_grid((0.0, TAU), (0.0, TAU), 48, 128);
@@ -75,8 +77,8 @@ fn setup_gaussian(
 
     let near = 0.1;
     let far = 4.0;
-    let projection = perspective(viewbox_aspect(view_box), 45.0_f32.to_radians(), near, far);
-    let viewport = Vec4::new(area.0 as f32, area.1 as f32, area.2 as f32, area.3 as f32);
+    let projection = perspective(view_box.aspect(), 45.0_f32.to_radians(), near, far);
+    let viewport = area.viewport();
     let camera = Camera { projection, model, viewport };
 
     // set up initial positions and velocities
@@ -108,8 +110,8 @@ fn setup_hole(view_box: ViewBox, area: ViewBox) -> (Hole, Camera, Vec<Polyline2>
 
     let near = 0.1;
     let far = 4.0;
-    let projection = perspective(viewbox_aspect(view_box), 45.0_f32.to_radians(), near, far);
-    let viewport = Vec4::new(area.0 as f32, area.1 as f32, area.2 as f32, area.3 as f32);
+    let projection = perspective(view_box.aspect(), 45.0_f32.to_radians(), near, far);
+    let viewport = area.viewport();
     let camera = Camera { projection, model, viewport };
 
     let size = 3.0;
@@ -120,8 +122,8 @@ fn setup_hole(view_box: ViewBox, area: ViewBox) -> (Hole, Camera, Vec<Polyline2>
 
 fn main() -> io::Result<()> {
     // set up paper
-    let mut paper = Paper::new(A4_LANDSCAPE, 0.5);
-    let area = pad(paper.view_box, 8);
+    let mut paper = Paper::new(ViewBox::A4.landscape(), 0.5);
+    let area = paper.view_box.pad(8.0);
 
     // set up pseudo random generator
     let mut rng = rand::rngs::StdRng::seed_from_u64(17);
@@ -135,19 +137,27 @@ fn main() -> io::Result<()> {
 
     for uv_polyline in uv_polylines {
         for xy_polyline in keep_xy(reproject(&uv_polyline, &geometry, &camera, area, near, far)) {
-            paper.add(xy_polyline);
+            // drop nearly collinear points, well below the pen width
+            paper.add(xy_polyline.simplify(0.02));
         }
     }
 
-    paper.optimize();
+    paper.clip(area);
+    let report = paper.merge(0.01);
+    println!("merge: {} -> {} polylines", report.before, report.after);
+    let report = paper.optimize();
+    println!("optimize: saved {:.0} mm of travel", report.saved());
     let (dl, ml) = paper.length();
     println!("draw: {dl} mm, move: {ml} mm");
     let output_path = "output.svg";
     paper.save(output_path)?;
     println!("wrote: {output_path}");
+    let preview_path = "output.png";
+    save_preview(&paper, preview_path, &PreviewOptions::default())?;
+    println!("wrote: {preview_path}");
 
     // estimate plotting time
-    let estimator = Estimator::best();
+    let estimator = Calibration::load_estimator(DEFAULT_PATH, DEFAULT_PROFILE)?;
     let duration = estimator.estimate(&paper, 2000.0, 8000.0);
     println!("Estimated time: {}", format_duration(duration));
 
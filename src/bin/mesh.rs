@@ -4,15 +4,17 @@ use std::{
     path::PathBuf,
 };
 
-use nalgebra_glm::{look_at, perspective, Vec2, Vec3, Vec4};
+use nalgebra_glm::{look_at, perspective, Vec2, Vec3};
 use plotter::{
+    calibration::{Calibration, DEFAULT_PATH, DEFAULT_PROFILE},
     camera::Camera,
     duration_extras::format_duration,
     mesh3::Mesh3,
     mesh3_io::load_obj,
-    paper::{pad, viewbox_aspect, Paper, ViewBox, A4_LANDSCAPE},
+    paper::Paper,
     polyline::Polyline2,
-    time_estimator::Estimator,
+    preview::{save_preview, PreviewOptions},
+    view_box::ViewBox,
 };
 
 const BARY_EPSILON: f32 = 1e-4;
@@ -43,8 +45,8 @@ fn setup_camera(view_box: ViewBox, area: ViewBox) -> Camera {
     let eye = Vec3::new(-2.7, -1.8, -2.0);
     let target = Vec3::new(0.0, 0.0, 1.6);
     let model = look_at(&eye, &target, &Vec3::new(0.0, 0.0, 1.0));
-    let projection = perspective(viewbox_aspect(view_box), 45.0_f32.to_radians(), 0.1, 4.0);
-    let viewport = Vec4::new(area.0 as f32, area.1 as f32, area.2 as f32, area.3 as f32);
+    let projection = perspective(view_box.aspect(), 45.0_f32.to_radians(), 0.1, 4.0);
+    let viewport = area.viewport();
     Camera { projection, model, viewport }
 }
 
@@ -291,8 +293,8 @@ fn parse_args() -> io::Result<Args> {
 fn main() -> io::Result<()> {
     let args = parse_args()?;
     let mesh = load_obj(&args.input_path)?;
-    let mut paper = Paper::new(A4_LANDSCAPE, 0.5);
-    let area = pad(paper.view_box, 8);
+    let mut paper = Paper::new(ViewBox::A4.landscape(), 0.5);
+    let area = paper.view_box.pad(8.0);
     let camera = setup_camera(paper.view_box, area);
     let projected = project_vertices(&mesh, &camera);
     let (triangles, edge_triangles) = triangulate(&mesh, &projected);
@@ -304,14 +306,21 @@ fn main() -> io::Result<()> {
         }
     }
 
-    paper.optimize();
+    paper.clip(area);
+    let report = paper.merge(0.01);
+    println!("merge: {} -> {} polylines", report.before, report.after);
+    let report = paper.optimize();
+    println!("optimize: saved {:.0} mm of travel", report.saved());
     let (dl, ml) = paper.length();
     println!("draw: {dl} mm, move: {ml} mm");
     let output_path = args.output_path.to_str().unwrap();
     paper.save(output_path)?;
     println!("wrote: {output_path}");
+    let preview_path = args.output_path.with_extension("png");
+    save_preview(&paper, &preview_path, &PreviewOptions::default())?;
+    println!("wrote: {}", preview_path.display());
 
-    let estimator = Estimator::best();
+    let estimator = Calibration::load_estimator(DEFAULT_PATH, DEFAULT_PROFILE)?;
     let duration = estimator.estimate(&paper, 2000.0, 8000.0);
     println!("Estimated time: {}", format_duration(duration));
 
@@ -16,6 +16,7 @@ use plotter::polyline::Polyline2;
 use plotter::resolution::Resolution;
 use plotter::skia_utils::draw_polylines_z;
 use plotter::uv2xy::reproject;
+use plotter::view_box::ViewBox;
 use rand::distributions::Distribution;
 use rand::rngs::StdRng;
 use rand::Rng;
@@ -491,7 +492,7 @@ fn trace_field(field: &Spiral, position: &Vec2, n: usize, dt: f32) -> Polyline2
         points.push(p);
         p += field.at(&p) * dt;
     }
-    Polyline2 { points }
+    Polyline2 { points, closed: false }
 }
 
 fn render_frame(
@@ -520,7 +521,7 @@ fn render_frame(
             uv_polyline,
             geometry,
             camera,
-            (0, 0, resolution.width as i32, resolution.height as i32),
+            ViewBox::size(resolution.width as f32, resolution.height as f32),
             NEAR,
             FAR,
         );
@@ -0,0 +1,254 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, BufWriter, ErrorKind, Write},
+    path::Path,
+    time::Duration,
+};
+
+use crate::{
+    paper::Paper,
+    time_estimator::{fit_to, residuals, Estimator, Measurement, Summary, Toolpath, CORNER_EDGES},
+};
+
+/// Where the binaries look for a calibration, and the profile they use
+pub const DEFAULT_PATH: &str = "calibration.txt";
+pub const DEFAULT_PROFILE: &str = "idraw";
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message.into())
+}
+
+/// A plot timed on the machine, speeds in mm/min
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimedPlot {
+    pub summary: Summary,
+    pub draw_speed: f32,
+    pub move_speed: f32,
+    pub duration: Duration,
+}
+
+/// Timed plots of one machine
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Profile {
+    pub plots: Vec<TimedPlot>,
+}
+
+/// An estimator fitted to a profile and how far off it is for each plot
+pub struct Fit {
+    pub estimator: Estimator,
+    // estimated minus measured seconds
+    pub residuals: Vec<f32>,
+}
+
+impl Fit {
+    pub fn rms(&self) -> f32 {
+        let n = self.residuals.len().max(1) as f32;
+        (self.residuals.iter().map(|r| r * r).sum::<f32>() / n).sqrt()
+    }
+}
+
+impl Profile {
+    // the paper as plotted, speeds in mm/min
+    pub fn add_paper(
+        &mut self,
+        paper: &Paper,
+        draw_speed: f32,
+        move_speed: f32,
+        duration: Duration,
+    ) {
+        self.plots.push(TimedPlot {
+            summary: Toolpath::from_paper(paper).summary(),
+            draw_speed,
+            move_speed,
+            duration,
+        });
+    }
+
+    // the plots with toolpaths rebuilt from their summaries
+    pub fn measurements(&self) -> Vec<Measurement> {
+        self.plots
+            .iter()
+            .map(|plot| Measurement {
+                toolpath: Toolpath::from_summary(&plot.summary),
+                draw_speed: plot.draw_speed,
+                move_speed: plot.move_speed,
+                duration: plot.duration,
+            })
+            .collect()
+    }
+
+    // the default estimator until something was measured
+    pub fn estimator(&self) -> Estimator {
+        if self.plots.is_empty() {
+            Estimator::default()
+        } else {
+            fit_to(&self.measurements())
+        }
+    }
+
+    pub fn fit(&self) -> Fit {
+        let estimator = self.estimator();
+        Fit {
+            estimator,
+            residuals: residuals(&estimator, &self.measurements()),
+        }
+    }
+}
+
+/// Measurements of several machines by name, stored as text:
+///
+/// ```text
+/// idraw:
+/// measurement <draw speed> <move speed> <seconds> <draw length> <move length> <pen lifts> <corners>...
+/// ```
+///
+/// with one corner count for every turning angle bin of `CORNER_EDGES`.
+/// Lines starting with `#` are comments.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Calibration {
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+fn parse_numbers(line_number: usize, values: &str) -> io::Result<Vec<f32>> {
+    values
+        .split_whitespace()
+        .map(|value| {
+            value.parse::<f32>().map_err(|err| {
+                invalid_data(format!("line {line_number}: failed to parse `{value}`: {err}"))
+            })
+        })
+        .collect()
+}
+
+impl Calibration {
+    pub fn read(content: &str) -> io::Result<Calibration> {
+        let mut calibration = Calibration::default();
+        let mut profile: Option<&mut Profile> = None;
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_suffix(':') {
+                let name = name.trim();
+                if name.is_empty() {
+                    return Err(invalid_data(format!("line {line_number}: empty profile name")));
+                }
+                profile = Some(calibration.profiles.entry(name.to_string()).or_default());
+                continue;
+            }
+
+            let profile = profile.as_mut().ok_or_else(|| {
+                invalid_data(format!("line {line_number}: measurement without profile"))
+            })?;
+            let (keyword, values) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            if keyword != "measurement" {
+                return Err(invalid_data(format!("line {line_number}: unknown entry `{keyword}`")));
+            }
+            let numbers = parse_numbers(line_number, values)?;
+            let Some((&values, corners)) = numbers.split_first_chunk::<6>() else {
+                return Err(invalid_data(format!(
+                    "line {line_number}: expected speeds, seconds, lengths, pen lifts and corners"
+                )));
+            };
+            let [draw_speed, move_speed, seconds, draw_length, move_length, pen_lifts] = values;
+            let bins = CORNER_EDGES.len() - 1;
+            if corners.len() != bins {
+                return Err(invalid_data(format!(
+                    "line {line_number}: expected {bins} corner counts, found {}",
+                    corners.len()
+                )));
+            }
+            if !(seconds >= 0.0 && seconds.is_finite()) {
+                return Err(invalid_data(format!("line {line_number}: invalid duration")));
+            }
+            let count = |value: f32| {
+                if value >= 0.0 && value.fract() == 0.0 {
+                    Ok(value as usize)
+                } else {
+                    Err(invalid_data(format!("line {line_number}: invalid count `{value}`")))
+                }
+            };
+            let mut summary = Summary {
+                draw_length,
+                move_length,
+                pen_lifts: count(pen_lifts)?,
+                ..Summary::default()
+            };
+            for (bin, &corners) in summary.corners.iter_mut().zip(corners) {
+                *bin = count(corners)?;
+            }
+            profile.plots.push(TimedPlot {
+                summary,
+                draw_speed,
+                move_speed,
+                duration: Duration::from_secs_f32(seconds),
+            });
+        }
+        Ok(calibration)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Calibration> {
+        Calibration::read(&fs::read_to_string(path)?)
+    }
+
+    // an empty calibration if the file does not exist yet
+    pub fn load_or_default(path: impl AsRef<Path>) -> io::Result<Calibration> {
+        match fs::read_to_string(path) {
+            Ok(content) => Calibration::read(&content),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Calibration::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        for (name, profile) in &self.profiles {
+            writeln!(writer, "{name}:")?;
+            for plot in &profile.plots {
+                let s = plot.summary;
+                write!(
+                    writer,
+                    "measurement {} {} {} {} {} {}",
+                    plot.draw_speed,
+                    plot.move_speed,
+                    plot.duration.as_secs_f32(),
+                    s.draw_length,
+                    s.move_length,
+                    s.pen_lifts
+                )?;
+                for corners in s.corners {
+                    write!(writer, " {corners}")?;
+                }
+                writeln!(writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+
+    // The estimator fitted to the profile in the file at `path`, or
+    // `Estimator::best` while there is no such file or profile
+    pub fn load_estimator(path: impl AsRef<Path>, name: &str) -> io::Result<Estimator> {
+        let calibration = Calibration::load_or_default(path)?;
+        Ok(match calibration.profile(name) {
+            Some(profile) if !profile.plots.is_empty() => profile.estimator(),
+            _ => Estimator::best(),
+        })
+    }
+
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles.get(name)
+    }
+
+    // creates the profile if needed
+    pub fn profile_mut(&mut self, name: &str) -> &mut Profile {
+        self.profiles.entry(name.to_string()).or_default()
+    }
+}
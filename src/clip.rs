@@ -0,0 +1,198 @@
+use nalgebra_glm::{TVec, Vec2};
+
+use crate::{
+    polyline::{Polyline, Polyline2},
+    view_box::ViewBox,
+};
+
+/// Which part of a polyline to keep when clipping against polygons
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClipMode {
+    Inside,
+    // inverse clipping, cuts holes
+    Outside,
+}
+
+fn xy<const N: usize>(point: &TVec<f32, N>) -> Vec2 {
+    Vec2::new(point[0], point[1])
+}
+
+fn cross(a: &Vec2, b: &Vec2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+// Builds polylines from the parameter intervals of each segment that are kept.
+// Intervals touching at a vertex continue the same polyline. Closed polylines
+// are cut open and only stay closed when nothing was removed.
+fn assemble<const N: usize, F>(polyline: &Polyline<N>, mut intervals: F) -> Vec<Polyline<N>>
+where
+    F: FnMut(&Vec2, &Vec2) -> Vec<(f32, f32)>,
+{
+    if polyline.closed && polyline.points.len() > 1 {
+        let path: Polyline<N> = polyline.points_drawn().copied().collect();
+        let mut pieces = assemble(&path, intervals);
+        let start = path.points.first();
+        let around = pieces.first().and_then(|p| p.points.first()) == start
+            && pieces.last().and_then(|p| p.points.last()) == start;
+        // nothing was cut away
+        if around && pieces.len() == 1 {
+            return vec![polyline.clone()];
+        }
+        // pieces that meet at the first point are one line
+        if around && pieces.len() > 1 {
+            let first = pieces.remove(0);
+            pieces.last_mut().unwrap().points.extend(first.points.into_iter().skip(1));
+        }
+        return pieces;
+    }
+
+    let mut pieces = Vec::new();
+    let mut current = Polyline::new();
+    // true if the current piece ends at the start of the next segment
+    let mut connected = false;
+    for segment in polyline.points.windows(2) {
+        let (a, b) = (segment[0], segment[1]);
+        let kept = intervals(&xy(&a), &xy(&b));
+        for &(t0, t1) in &kept {
+            let end = a.lerp(&b, t1);
+            if connected && t0 <= 0.0 {
+                current.add(end);
+            } else {
+                if current.points.len() >= 2 {
+                    pieces.push(current);
+                }
+                current = Polyline::new();
+                current.add(a.lerp(&b, t0));
+                current.add(end);
+            }
+            connected = t1 >= 1.0;
+        }
+        if kept.is_empty() {
+            connected = false;
+        }
+    }
+    if current.points.len() >= 2 {
+        pieces.push(current);
+    }
+    pieces
+}
+
+// Liang-Barsky, returns the parameter interval of the segment inside the box
+fn clip_segment(a: &Vec2, b: &Vec2, view_box: ViewBox) -> Option<(f32, f32)> {
+    let (x0, y0, x1, y1) = (view_box.x, view_box.y, view_box.right(), view_box.bottom());
+    let d = b - a;
+    let mut t0: f32 = 0.0;
+    let mut t1: f32 = 1.0;
+    for (p, q) in [
+        (-d.x, a.x - x0),
+        (d.x, x1 - a.x),
+        (-d.y, a.y - y0),
+        (d.y, y1 - a.y),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            t0 = t0.max(q / p);
+        } else {
+            t1 = t1.min(q / p);
+        }
+    }
+    (t0 < t1 || (t0 == t1 && d.norm_squared() == 0.0)).then_some((t0, t1))
+}
+
+/// Splits the polyline where it crosses the view box and keeps the parts
+/// inside. Only the first two coordinates are considered, the others are
+/// interpolated.
+pub fn clip_to_view_box<const N: usize>(
+    polyline: &Polyline<N>,
+    view_box: ViewBox,
+) -> Vec<Polyline<N>> {
+    if polyline.points.len() == 1 {
+        let p = xy(&polyline.points[0]);
+        return match clip_segment(&p, &p, view_box) {
+            Some(_) => vec![polyline.clone()],
+            None => Vec::new(),
+        };
+    }
+    assemble(polyline, |a, b| clip_segment(a, b, view_box).into_iter().collect())
+}
+
+/// Even-odd point in polygon test. Polygons are implicitly closed.
+pub fn inside_polygons(point: &Vec2, polygons: &[Polyline2]) -> bool {
+    let mut inside = false;
+    for polygon in polygons {
+        let n = polygon.points.len();
+        for i in 0..n {
+            let a = polygon.points[i];
+            let b = polygon.points[(i + 1) % n];
+            if (a.y > point.y) != (b.y > point.y) {
+                let x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+                if point.x < x {
+                    inside = !inside;
+                }
+            }
+        }
+    }
+    inside
+}
+
+// parameters where the segment crosses any polygon edge
+fn crossings(a: &Vec2, b: &Vec2, polygons: &[Polyline2]) -> Vec<f32> {
+    let d = b - a;
+    let mut ts = Vec::new();
+    for polygon in polygons {
+        let n = polygon.points.len();
+        for i in 0..n {
+            let c = polygon.points[i];
+            let e = polygon.points[(i + 1) % n] - c;
+            let denominator = cross(&d, &e);
+            if denominator.abs() < f32::EPSILON {
+                continue;
+            }
+            let ac = c - a;
+            let t = cross(&ac, &e) / denominator;
+            let s = cross(&ac, &d) / denominator;
+            if (0.0..=1.0).contains(&s) && t > 0.0 && t < 1.0 {
+                ts.push(t);
+            }
+        }
+    }
+    ts.sort_by(f32::total_cmp);
+    ts
+}
+
+/// Clips the polyline against polygons, which may be concave and may contain
+/// holes (even-odd rule). The polyline is split at the exact intersections.
+pub fn clip_to_polygons<const N: usize>(
+    polyline: &Polyline<N>,
+    polygons: &[Polyline2],
+    mode: ClipMode,
+) -> Vec<Polyline<N>> {
+    let keep = |point: &Vec2| inside_polygons(point, polygons) == (mode == ClipMode::Inside);
+    if polyline.points.len() == 1 {
+        return match keep(&xy(&polyline.points[0])) {
+            true => vec![polyline.clone()],
+            false => Vec::new(),
+        };
+    }
+    assemble(polyline, |a, b| {
+        let mut ts = vec![0.0];
+        ts.extend(crossings(a, b, polygons));
+        ts.push(1.0);
+        let mut kept: Vec<(f32, f32)> = Vec::new();
+        for pair in ts.windows(2) {
+            let (t0, t1) = (pair[0], pair[1]);
+            if !keep(&a.lerp(b, 0.5 * (t0 + t1))) {
+                continue;
+            }
+            // merge with the previous interval if they touch
+            match kept.last_mut() {
+                Some(last) if last.1 == t0 => last.1 = t1,
+                _ => kept.push((t0, t1)),
+            }
+        }
+        kept
+    })
+}
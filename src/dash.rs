@@ -0,0 +1,124 @@
+use nalgebra_glm::Vec2;
+
+use crate::polyline::Polyline2;
+
+// distance along the path at each drawn point
+fn distances(points: &[Vec2]) -> Vec<f32> {
+    let mut total = 0.0;
+    let mut distances = vec![0.0];
+    for pair in points.windows(2) {
+        total += (pair[1] - pair[0]).norm();
+        distances.push(total);
+    }
+    distances
+}
+
+fn point_at(points: &[Vec2], distances: &[f32], s: f32) -> Vec2 {
+    let i = distances.partition_point(|&d| d <= s).clamp(1, points.len().max(2) - 1);
+    let length = distances[i] - distances[i - 1];
+    if length <= 0.0 {
+        return points[i];
+    }
+    points[i - 1].lerp(&points[i], (s - distances[i - 1]) / length)
+}
+
+// the part of the path from `a` to `b`, keeping the corners in between
+fn slice(points: &[Vec2], distances: &[f32], a: f32, b: f32) -> Polyline2 {
+    let mut part = Polyline2::new();
+    part.add(point_at(points, distances, a));
+    if b > a {
+        for (point, &d) in points.iter().zip(distances) {
+            if d > a && d < b {
+                part.add(*point);
+            }
+        }
+        part.add(point_at(points, distances, b));
+    }
+    part
+}
+
+/// Cuts the polyline into dashes. The pattern alternates dash and gap
+/// lengths in mm, starting with a dash, and is repeated twice if it has an
+/// odd number of entries, like SVG's `stroke-dasharray`. `phase` is how far
+/// into the pattern the polyline starts. Dashes follow the polyline around
+/// corners, and on closed polylines a dash may run across the start. A dash
+/// of zero length is a single point, which is plotted as a dot.
+///
+/// Patterns without a positive length, or repeating more than a million
+/// times along the polyline, leave it solid.
+pub fn dash(polyline: &Polyline2, pattern: &[f32], phase: f32) -> Vec<Polyline2> {
+    let period: f32 = pattern.iter().sum();
+    if !pattern.iter().all(|length| length.is_finite() && *length >= 0.0) || period <= 0.0 {
+        return vec![polyline.clone()];
+    }
+    let points: Vec<Vec2> = polyline.points_drawn().copied().collect();
+    if points.len() < 2 {
+        return vec![polyline.clone()];
+    }
+    let distances = distances(&points);
+    let total = *distances.last().unwrap();
+    if period < total * 1e-6 {
+        return vec![polyline.clone()];
+    }
+    let pattern = if pattern.len().is_multiple_of(2) {
+        pattern.to_vec()
+    } else {
+        pattern.repeat(2)
+    };
+
+    // dash intervals along the path, counting whole periods so that the
+    // position never stalls on rounding
+    let mut intervals = Vec::new();
+    let start = -phase.rem_euclid(period);
+    // odd patterns repeat after twice their period
+    let cycle: f32 = pattern.iter().sum();
+    let repeats = ((total - start) / cycle) as usize + 1;
+    // the end of a closed polyline is its start, so no dot there
+    let ends = |s: f32| {
+        if polyline.closed {
+            s < total
+        } else {
+            s <= total
+        }
+    };
+    'walk: for k in 0..repeats {
+        let mut s = start + k as f32 * cycle;
+        for (index, &length) in pattern.iter().enumerate() {
+            if !ends(s) {
+                break 'walk;
+            }
+            let (a, b) = (s.max(0.0), (s + length).min(total));
+            let dot = length == 0.0 && s >= 0.0;
+            if index % 2 == 0 && (b > a || dot) {
+                intervals.push((a, b));
+            }
+            s += length;
+        }
+    }
+
+    let mut dashes: Vec<Polyline2> =
+        intervals.iter().map(|&(a, b)| slice(&points, &distances, a, b)).collect();
+    // join the dash over the start of a closed polyline
+    if let (true, Some(&(a, b)), Some(&(c, d))) =
+        (polyline.closed, intervals.first(), intervals.last())
+    {
+        if intervals.len() > 1 && a == 0.0 && b > a && d == total && d > c {
+            let first = dashes.remove(0);
+            dashes.last_mut().unwrap().points.extend(&first.points[1..]);
+        }
+    }
+    dashes
+}
+
+/// Points every `spacing` mm along the polyline, starting `phase` mm into
+/// the spacing, for dotted lines with `Paper::dots`.
+pub fn dots(polyline: &Polyline2, spacing: f32, phase: f32) -> Vec<Vec2> {
+    if spacing.is_nan() || spacing <= 0.0 {
+        return Vec::new();
+    }
+    dash(polyline, &[0.0, spacing], phase)
+        .into_iter()
+        .filter(|dot| dot.points.len() == 1)
+        .map(|dot| dot.points[0])
+        .collect()
+}
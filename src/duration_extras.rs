@@ -11,3 +11,16 @@ pub fn format_duration(duration: Duration) -> String {
     }
     format!("{minutes}m {seconds}s")
 }
+
+// accepts seconds, "m:ss" or "h:mm:ss"
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let mut seconds = 0.0;
+    for part in text.trim().split(':') {
+        let value = part.trim().parse::<f32>().ok()?;
+        if !(value >= 0.0 && value.is_finite()) {
+            return None;
+        }
+        seconds = seconds * 60.0 + value;
+    }
+    Some(Duration::from_secs_f32(seconds))
+}
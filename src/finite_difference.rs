@@ -0,0 +1,143 @@
+use nalgebra_glm::{Vec2, Vec3};
+
+use crate::{
+    geometry::{DifferentiableGeometry, Geometry},
+    sdf::SDF,
+};
+
+// a balance between truncation and f32 rounding for second derivatives,
+// which are nested differences two steps wide
+pub const DEFAULT_STEP: f32 = 1e-2;
+
+/// Makes any geometry differentiable with central finite differences of
+/// `step` in uv. Derivatives of any order are nested differences, so the
+/// error grows with the order.
+pub struct FiniteDifference<G> {
+    pub geometry: G,
+    pub step: f32,
+}
+
+impl<G> FiniteDifference<G> {
+    pub fn new(geometry: G) -> Self {
+        Self { geometry, step: DEFAULT_STEP }
+    }
+
+    pub fn with_step(geometry: G, step: f32) -> Self {
+        Self { geometry, step }
+    }
+}
+
+impl<G: Geometry> Geometry for FiniteDifference<G> {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        self.geometry.evaluate(p)
+    }
+}
+
+impl<G: Geometry> DifferentiableGeometry for FiniteDifference<G> {
+    fn du(&self) -> impl DifferentiableGeometry {
+        Difference { geometry: &self.geometry, step: self.step, u: 1, v: 0 }
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        Difference { geometry: &self.geometry, step: self.step, u: 0, v: 1 }
+    }
+}
+
+impl<G: SDF> SDF for FiniteDifference<G> {
+    fn sdf(&self, position: &Vec3) -> f32 {
+        self.geometry.sdf(position)
+    }
+    fn is_distance_bound(&self) -> bool {
+        self.geometry.is_distance_bound()
+    }
+}
+
+/// The partial derivative of a geometry `u` times by u and `v` times by v
+pub struct Difference<'a, G> {
+    geometry: &'a G,
+    step: f32,
+    u: usize,
+    v: usize,
+}
+
+impl<G: Geometry> Difference<'_, G> {
+    fn at(&self, p: &Vec2, u: usize, v: usize) -> Vec3 {
+        let h = self.step;
+        let central = |offset: Vec2, u, v| {
+            (self.at(&(p + offset), u, v) - self.at(&(p - offset), u, v)) / (2.0 * h)
+        };
+        match (u, v) {
+            (0, 0) => self.geometry.evaluate(p),
+            (0, v) => central(Vec2::new(0.0, h), 0, v - 1),
+            (u, v) => central(Vec2::new(h, 0.0), u - 1, v),
+        }
+    }
+}
+
+impl<G: Geometry> Geometry for Difference<'_, G> {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        self.at(p, self.u, self.v)
+    }
+}
+
+impl<G: Geometry> DifferentiableGeometry for Difference<'_, G> {
+    fn du(&self) -> impl DifferentiableGeometry {
+        Difference { u: self.u + 1, ..*self }
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        Difference { v: self.v + 1, ..*self }
+    }
+}
+
+/// An analytic derivative that differs from the numeric one
+#[derive(Clone, Debug)]
+pub struct Mismatch {
+    // "du", "dudv", ... or "metric"
+    pub derivative: &'static str,
+    pub at: Vec2,
+    pub analytic: Vec3,
+    pub numeric: Vec3,
+}
+
+/// Compares the analytic first and second derivatives and the metric of a
+/// geometry with finite differences of `DEFAULT_STEP` on a `samples` ×
+/// `samples` grid from `min` to `max` in uv. Errors are relative to the size
+/// of the numeric derivative plus one, and those above `tolerance` are
+/// returned.
+pub fn check_derivatives(
+    geometry: &impl DifferentiableGeometry,
+    min: Vec2,
+    max: Vec2,
+    samples: usize,
+    tolerance: f32,
+) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+    let samples = samples.max(2);
+    for i in 0..samples {
+        for j in 0..samples {
+            let t = Vec2::new(i as f32, j as f32) / (samples - 1) as f32;
+            let p = min + (max - min).component_mul(&t);
+            let numeric = |u, v| Difference { geometry, step: DEFAULT_STEP, u, v }.evaluate(&p);
+            let (du, dv) = (numeric(1, 0), numeric(0, 1));
+            let metric = geometry.metric(&p);
+            let pairs = [
+                ("du", geometry.du().evaluate(&p), du),
+                ("dv", geometry.dv().evaluate(&p), dv),
+                ("dudu", geometry.du().du().evaluate(&p), numeric(2, 0)),
+                ("dudv", geometry.du().dv().evaluate(&p), numeric(1, 1)),
+                ("dvdu", geometry.dv().du().evaluate(&p), numeric(1, 1)),
+                ("dvdv", geometry.dv().dv().evaluate(&p), numeric(0, 2)),
+                (
+                    "metric",
+                    Vec3::new(metric[(0, 0)], metric[(0, 1)], metric[(1, 1)]),
+                    Vec3::new(du.dot(&du), du.dot(&dv), dv.dot(&dv)),
+                ),
+            ];
+            for (derivative, analytic, numeric) in pairs {
+                if (analytic - numeric).norm() > tolerance * (1.0 + numeric.norm()) {
+                    mismatches.push(Mismatch { derivative, at: p, analytic, numeric });
+                }
+            }
+        }
+    }
+    mismatches
+}
@@ -0,0 +1,168 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+use nalgebra_glm::Vec2;
+
+use crate::{paper::Paper, polyline::Polyline2, view_box::ViewBox};
+
+/// How the pen is lowered and raised
+#[derive(Clone, Copy, Debug)]
+pub enum PenLift {
+    /// Servo on the spindle output. `M3 S<power>` lowers the pen and `M5`
+    /// raises it. `dwell` is the time in seconds to wait for the servo.
+    Servo { power: u32, dwell: f32 },
+    /// Z axis moves to `up` or `down` (mm) with the given feed rate (mm/min)
+    Z { up: f32, down: f32, feed: f32 },
+}
+
+pub struct GcodeOptions {
+    pub pen_lift: PenLift,
+    // feed rates in mm/min, same units as `Estimator::estimate`
+    pub draw_speed: f32,
+    pub move_speed: f32,
+    // machine position the job starts from and returns to
+    pub home: Vec2,
+    // GRBL has y pointing up while SVG has y pointing down
+    pub flip_y: bool,
+    // stop with M0 before each layer after the first so the pen can be swapped
+    pub pause_between_layers: bool,
+    // with a Z lift the pen only rises this far (mm) between two dots
+    pub dot_hop: f32,
+}
+
+impl Default for GcodeOptions {
+    fn default() -> Self {
+        Self {
+            pen_lift: PenLift::Servo { power: 1000, dwell: 0.15 },
+            draw_speed: 2000.0,
+            move_speed: 8000.0,
+            home: Vec2::zeros(),
+            flip_y: false,
+            pause_between_layers: true,
+            dot_hop: 1.0,
+        }
+    }
+}
+
+fn to_machine(point: &Vec2, view_box: ViewBox, flip_y: bool) -> Vec2 {
+    if flip_y {
+        Vec2::new(point.x, view_box.y + view_box.bottom() - point.y)
+    } else {
+        *point
+    }
+}
+
+struct Emitter<'a> {
+    options: &'a GcodeOptions,
+    lines: Vec<String>,
+    feed: Option<f32>,
+}
+
+impl Emitter<'_> {
+    fn pen_up(&mut self) {
+        match self.options.pen_lift {
+            PenLift::Servo { dwell, .. } => {
+                self.lines.push("M5".to_string());
+                self.lines.push(format!("G4 P{dwell:.3}"));
+            }
+            PenLift::Z { up, feed, .. } => {
+                self.lines.push(format!("G1 Z{up:.3} F{feed}"));
+                self.feed = Some(feed);
+            }
+        }
+    }
+
+    fn pen_down(&mut self) {
+        match self.options.pen_lift {
+            PenLift::Servo { power, dwell } => {
+                self.lines.push(format!("M3 S{power}"));
+                self.lines.push(format!("G4 P{dwell:.3}"));
+            }
+            PenLift::Z { down, feed, .. } => {
+                self.lines.push(format!("G1 Z{down:.3} F{feed}"));
+                self.feed = Some(feed);
+            }
+        }
+    }
+
+    // lifts the pen just clear of the paper to go to the next dot
+    fn hop(&mut self) {
+        match self.options.pen_lift {
+            PenLift::Servo { .. } => self.pen_up(),
+            PenLift::Z { up, down, feed } => {
+                let z = down + (up - down).clamp(-self.options.dot_hop, self.options.dot_hop);
+                self.lines.push(format!("G1 Z{z:.3} F{feed}"));
+                self.feed = Some(feed);
+            }
+        }
+    }
+
+    // linear move, the feed rate is modal so only emit it when it changes
+    fn line_to(&mut self, point: &Vec2, feed: f32) {
+        if self.feed == Some(feed) {
+            self.lines.push(format!("G1 X{:.3} Y{:.3}", point.x, point.y));
+        } else {
+            self.lines.push(format!("G1 X{:.3} Y{:.3} F{feed}", point.x, point.y));
+            self.feed = Some(feed);
+        }
+    }
+}
+
+/// Converts the paper to G-code lines for a GRBL controller. Travel moves
+/// use `G1` at `move_speed` so the plot takes as long as estimated.
+pub fn gcode_lines(paper: &Paper, options: &GcodeOptions) -> Vec<String> {
+    let mut emitter = Emitter { options, lines: Vec::new(), feed: None };
+    emitter.lines.push("G21".to_string()); // millimetres
+    emitter.lines.push("G90".to_string()); // absolute coordinates
+    emitter.pen_up();
+
+    let mut first_layer = true;
+    for layer in paper.layers() {
+        if layer.polylines().is_empty() {
+            continue;
+        }
+        if !first_layer && options.pause_between_layers {
+            emitter.line_to(&options.home, options.move_speed);
+            emitter.lines.push(format!("(change pen: {})", layer.name));
+            emitter.lines.push("M0".to_string());
+        }
+        first_layer = false;
+        let is_dot = |polyline: &Polyline2| polyline.points.len() == 1;
+        let mut polylines = layer.polylines().iter().peekable();
+        while let Some(polyline) = polylines.next() {
+            let mut points =
+                polyline.points_drawn().map(|p| to_machine(p, paper.view_box, options.flip_y));
+            let Some(start) = points.next() else {
+                continue;
+            };
+            emitter.line_to(&start, options.move_speed);
+            emitter.pen_down();
+            for point in points {
+                emitter.line_to(&point, options.draw_speed);
+            }
+            if is_dot(polyline) && polylines.peek().is_some_and(|next| is_dot(next)) {
+                emitter.hop();
+            } else {
+                emitter.pen_up();
+            }
+        }
+    }
+
+    emitter.line_to(&options.home, options.move_speed);
+    emitter.lines.push("M2".to_string());
+    emitter.lines
+}
+
+pub fn write_gcode(paper: &Paper, writer: impl Write, options: &GcodeOptions) -> io::Result<()> {
+    let mut writer = BufWriter::new(writer);
+    for line in gcode_lines(paper, options) {
+        writeln!(writer, "{line}")?;
+    }
+    writer.flush()
+}
+
+pub fn save_gcode(paper: &Paper, filename: &str, options: &GcodeOptions) -> io::Result<()> {
+    write_gcode(paper, File::create(filename)?, options)
+}
@@ -0,0 +1,128 @@
+use nalgebra_glm::{rotate_vec2, Mat2x2, Vec2};
+
+use crate::autodiff::{Jet, Scalar};
+
+use super::heightmap::Heightmap;
+
+// Combinations of heightmaps. Each one combines the jets of its operands,
+// so derivatives carry through by the chain and product rules. See
+// `Heightmap::plus` and the other methods to build them.
+
+/// An analytic function applied to a height
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Function {
+    Exp,
+    Ln,
+    Sqrt,
+    Sin,
+    Cos,
+    Tanh,
+    Powi(i32),
+}
+
+impl Function {
+    pub fn apply<S: Scalar>(self, x: S) -> S {
+        match self {
+            Function::Exp => x.exp(),
+            Function::Ln => x.ln(),
+            Function::Sqrt => x.sqrt(),
+            Function::Sin => x.sin(),
+            Function::Cos => x.cos(),
+            Function::Tanh => x.tanh(),
+            Function::Powi(n) => x.powi(n),
+        }
+    }
+}
+
+pub struct Difference<A, B> {
+    pub a: A,
+    pub b: B,
+}
+
+impl<A: Heightmap, B: Heightmap> Heightmap for Difference<A, B> {
+    fn z(&self, p: &Vec2) -> f32 {
+        self.a.z(p) - self.b.z(p)
+    }
+    fn jet(&self, p: &Vec2) -> Jet {
+        self.a.jet(p) - self.b.jet(p)
+    }
+}
+
+pub struct Product<A, B> {
+    pub a: A,
+    pub b: B,
+}
+
+impl<A: Heightmap, B: Heightmap> Heightmap for Product<A, B> {
+    fn z(&self, p: &Vec2) -> f32 {
+        self.a.z(p) * self.b.z(p)
+    }
+    fn jet(&self, p: &Vec2) -> Jet {
+        self.a.jet(p) * self.b.jet(p)
+    }
+}
+
+pub struct Scaled<T> {
+    pub heightmap: T,
+    pub factor: f32,
+}
+
+impl<T: Heightmap> Heightmap for Scaled<T> {
+    fn z(&self, p: &Vec2) -> f32 {
+        self.heightmap.z(p) * self.factor
+    }
+    fn jet(&self, p: &Vec2) -> Jet {
+        self.heightmap.jet(p) * self.factor
+    }
+}
+
+pub struct Translated<T> {
+    pub heightmap: T,
+    pub offset: Vec2,
+}
+
+impl<T: Heightmap> Heightmap for Translated<T> {
+    fn z(&self, p: &Vec2) -> f32 {
+        self.heightmap.z(&(p - self.offset))
+    }
+    fn jet(&self, p: &Vec2) -> Jet {
+        self.heightmap.jet(&(p - self.offset))
+    }
+}
+
+pub struct Rotated<T> {
+    pub heightmap: T,
+    // radians, counter-clockwise around the uv origin
+    pub angle: f32,
+}
+
+impl<T: Heightmap> Heightmap for Rotated<T> {
+    fn z(&self, p: &Vec2) -> f32 {
+        self.heightmap.z(&rotate_vec2(p, -self.angle))
+    }
+    // z(R^T p) has the gradient R g and the Hessian R H R^T
+    fn jet(&self, p: &Vec2) -> Jet {
+        let jet = self.heightmap.jet(&rotate_vec2(p, -self.angle));
+        let (sin, cos) = self.angle.sin_cos();
+        let rotation = Mat2x2::new(cos, -sin, sin, cos);
+        Jet {
+            value: jet.value,
+            gradient: rotation * jet.gradient,
+            hessian: rotation * jet.hessian * rotation.transpose(),
+        }
+    }
+}
+
+pub struct Map<T> {
+    pub heightmap: T,
+    pub function: Function,
+}
+
+impl<T: Heightmap> Heightmap for Map<T> {
+    fn z(&self, p: &Vec2) -> f32 {
+        self.function.apply(self.heightmap.z(p))
+    }
+    fn jet(&self, p: &Vec2) -> Jet {
+        self.function.apply(self.heightmap.jet(p))
+    }
+}
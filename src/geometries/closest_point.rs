@@ -0,0 +1,130 @@
+use nalgebra_glm::{Mat2x2, Vec2, Vec3};
+
+use crate::{
+    geometry::{DifferentiableGeometry, Geometry},
+    sdf::SDF,
+};
+
+/// Gives any differentiable geometry an SDF for occlusion, for surfaces
+/// without an implicit function like the Klein bottle. The distance to the
+/// closest point is found from a grid of samples over `min` to `max` in uv
+/// and refined with Gauss-Newton steps. A one-sided surface has no normal to
+/// sign it by, so the SDF is that of a shell reaching `thickness` to either
+/// side of the surface: negative within the shell and positive elsewhere.
+/// The shell is far thinner than the steps of a line search, so `Tracer`
+/// sphere-traces it instead, as the level is a distance.
+pub struct ClosestPoint<G> {
+    pub geometry: G,
+    pub thickness: f32,
+    min: Vec2,
+    max: Vec2,
+    patches: Vec<Patch>,
+}
+
+// samples from a block of the uv grid with a sphere bounding their points,
+// so that blocks further away than the best sample so far can be skipped
+struct Patch {
+    center: Vec3,
+    radius: f32,
+    samples: Vec<(Vec2, Vec3)>,
+}
+
+// samples along each side of a patch
+const PATCH_SIZE: usize = 8;
+
+impl<G: DifferentiableGeometry> ClosestPoint<G> {
+    pub fn new(geometry: G, min: Vec2, max: Vec2, resolution: usize) -> Self {
+        let resolution = resolution.max(2);
+        let blocks = resolution.div_ceil(PATCH_SIZE);
+        let mut patches = Vec::with_capacity(blocks * blocks);
+        for block_i in 0..blocks {
+            for block_j in 0..blocks {
+                let mut samples = Vec::with_capacity(PATCH_SIZE * PATCH_SIZE);
+                for i in block_i * PATCH_SIZE..((block_i + 1) * PATCH_SIZE).min(resolution) {
+                    for j in block_j * PATCH_SIZE..((block_j + 1) * PATCH_SIZE).min(resolution) {
+                        let t = Vec2::new(i as f32, j as f32) / (resolution - 1) as f32;
+                        let uv = min + (max - min).component_mul(&t);
+                        samples.push((uv, geometry.evaluate(&uv)));
+                    }
+                }
+                let center =
+                    samples.iter().map(|(_, point)| point).sum::<Vec3>() / samples.len() as f32;
+                let radius =
+                    samples.iter().map(|(_, point)| (point - center).norm()).fold(0.0, f32::max);
+                patches.push(Patch { center, radius, samples });
+            }
+        }
+        Self { geometry, thickness: 1e-3, min, max, patches }
+    }
+
+    // uv of the surface point closest to the position
+    pub fn closest(&self, position: &Vec3) -> Vec2 {
+        let distance = |point: &Vec3| (point - position).norm_squared();
+        // no sample of a patch is closer than its bounding sphere
+        let bound = |patch: &Patch| ((patch.center - position).norm() - patch.radius).max(0.0);
+        // the nearest patch likely holds the closest sample, which then rules
+        // out most of the others
+        let nearest = (0..self.patches.len())
+            .min_by(|&a, &b| bound(&self.patches[a]).total_cmp(&bound(&self.patches[b])))
+            .unwrap();
+        let order =
+            std::iter::once(nearest).chain((0..self.patches.len()).filter(|&i| i != nearest));
+        let (mut uv, mut best) = (self.min, f32::INFINITY);
+        for patch in order.map(|i| &self.patches[i]) {
+            if bound(patch).powi(2) >= best {
+                continue;
+            }
+            for (sample, point) in &patch.samples {
+                let d = distance(point);
+                if d < best {
+                    (uv, best) = (*sample, d);
+                }
+            }
+        }
+        for _ in 0..8 {
+            let point = self.geometry.evaluate(&uv);
+            let (du, dv) = (self.geometry.du().evaluate(&uv), self.geometry.dv().evaluate(&uv));
+            let normal = Mat2x2::new(du.dot(&du), du.dot(&dv), dv.dot(&du), dv.dot(&dv));
+            let Some(inverse) = normal.try_inverse() else {
+                break;
+            };
+            let offset = position - point;
+            let step = inverse * Vec2::new(du.dot(&offset), dv.dot(&offset));
+            let next = (uv + step).zip_zip_map(&self.min, &self.max, |x, lo, hi| x.clamp(lo, hi));
+            let next_distance = distance(&self.geometry.evaluate(&next));
+            if next_distance >= best {
+                break;
+            }
+            (uv, best) = (next, next_distance);
+        }
+        uv
+    }
+}
+
+impl<G: Geometry> Geometry for ClosestPoint<G> {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        self.geometry.evaluate(p)
+    }
+}
+
+impl<G: DifferentiableGeometry> DifferentiableGeometry for ClosestPoint<G> {
+    fn du(&self) -> impl DifferentiableGeometry {
+        self.geometry.du()
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        self.geometry.dv()
+    }
+    fn metric(&self, p: &Vec2) -> Mat2x2 {
+        self.geometry.metric(p)
+    }
+}
+
+impl<G: DifferentiableGeometry> SDF for ClosestPoint<G> {
+    fn sdf(&self, position: &Vec3) -> f32 {
+        let uv = self.closest(position);
+        (position - self.geometry.evaluate(&uv)).norm() - self.thickness
+    }
+    fn is_distance_bound(&self) -> bool {
+        true
+    }
+}
@@ -1,9 +1,88 @@
-use nalgebra_glm::{Vec2, Vec3};
+use nalgebra_glm::{Mat2x2, Vec2, Vec3};
 
-use crate::geometry::Geometry;
+use crate::{
+    autodiff::Jet,
+    finite_difference::DEFAULT_STEP,
+    geometry::{DifferentiableGeometry, Geometry},
+    sdf::SDF,
+};
+
+use super::algebra::{Difference, Function, Map, Product, Rotated, Scaled, Translated};
+use super::sum::Sum;
 
 pub trait Heightmap {
     fn z(&self, p: &Vec2) -> f32;
+
+    // The height with its gradient and Hessian. Central differences unless
+    // overridden, e.g. by evaluating a generic height with `Jet::variables`.
+    fn jet(&self, p: &Vec2) -> Jet {
+        let h = DEFAULT_STEP;
+        let z = |du: f32, dv: f32| self.z(&(p + Vec2::new(du, dv) * h));
+        let center = z(0.0, 0.0);
+        let uv = (z(1.0, 1.0) - z(1.0, -1.0) - z(-1.0, 1.0) + z(-1.0, -1.0)) / (4.0 * h * h);
+        Jet {
+            value: center,
+            gradient: Vec2::new(z(1.0, 0.0) - z(-1.0, 0.0), z(0.0, 1.0) - z(0.0, -1.0)) / (2.0 * h),
+            hessian: Mat2x2::new(
+                (z(1.0, 0.0) - 2.0 * center + z(-1.0, 0.0)) / (h * h),
+                uv,
+                uv,
+                (z(0.0, 1.0) - 2.0 * center + z(0.0, -1.0)) / (h * h),
+            ),
+        }
+    }
+
+    fn plus<B: Heightmap>(self, other: B) -> Sum<Self, B>
+    where
+        Self: Sized,
+    {
+        Sum::new(self, other)
+    }
+
+    fn minus<B: Heightmap>(self, other: B) -> Difference<Self, B>
+    where
+        Self: Sized,
+    {
+        Difference { a: self, b: other }
+    }
+
+    fn times<B: Heightmap>(self, other: B) -> Product<Self, B>
+    where
+        Self: Sized,
+    {
+        Product { a: self, b: other }
+    }
+
+    fn scaled(self, factor: f32) -> Scaled<Self>
+    where
+        Self: Sized,
+    {
+        Scaled { heightmap: self, factor }
+    }
+
+    // moves the heightmap by `offset` in uv
+    fn translated(self, offset: Vec2) -> Translated<Self>
+    where
+        Self: Sized,
+    {
+        Translated { heightmap: self, offset }
+    }
+
+    // rotates the heightmap counter-clockwise around the uv origin
+    fn rotated(self, angle: f32) -> Rotated<Self>
+    where
+        Self: Sized,
+    {
+        Rotated { heightmap: self, angle }
+    }
+
+    // applies the function to the height
+    fn map(self, function: Function) -> Map<Self>
+    where
+        Self: Sized,
+    {
+        Map { heightmap: self, function }
+    }
 }
 
 impl<T: Heightmap> Geometry for T {
@@ -11,3 +90,62 @@ impl<T: Heightmap> Geometry for T {
         Vec3::new(p.x, p.y, self.z(&p))
     }
 }
+
+// (u, v, z) has the derivatives (1, 0, z_u) and (0, 1, z_v), and only z
+// has second derivatives
+impl<T: Heightmap> DifferentiableGeometry for T {
+    fn du(&self) -> impl DifferentiableGeometry {
+        HeightmapPartial { heightmap: self, u: 1, v: 0 }
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        HeightmapPartial { heightmap: self, u: 0, v: 1 }
+    }
+
+    // from a single jet
+    #[rustfmt::skip]
+    fn metric(&self, p: &Vec2) -> Mat2x2 {
+        let g = self.jet(p).gradient;
+        Mat2x2::new(
+            1.0 + g.x * g.x, g.x * g.y,
+            g.x * g.y, 1.0 + g.y * g.y,
+        )
+    }
+}
+
+// the height above the surface, negative below it
+impl<T: Heightmap> SDF for T {
+    fn sdf(&self, position: &Vec3) -> f32 {
+        self.z(&position.xy()) - position.z
+    }
+}
+
+/// A partial derivative of a heightmap up to the second order
+pub struct HeightmapPartial<'a, T> {
+    heightmap: &'a T,
+    u: usize,
+    v: usize,
+}
+
+impl<T: Heightmap> Geometry for HeightmapPartial<'_, T> {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        let jet = self.heightmap.jet(p);
+        match (self.u, self.v) {
+            (0, 0) => self.heightmap.evaluate(p),
+            (1, 0) => Vec3::new(1.0, 0.0, jet.gradient.x),
+            (0, 1) => Vec3::new(0.0, 1.0, jet.gradient.y),
+            (2, 0) => Vec3::new(0.0, 0.0, jet.hessian[(0, 0)]),
+            (1, 1) => Vec3::new(0.0, 0.0, jet.hessian[(0, 1)]),
+            (0, 2) => Vec3::new(0.0, 0.0, jet.hessian[(1, 1)]),
+            _ => panic!("heightmap derivatives stop at the second order"),
+        }
+    }
+}
+
+impl<T: Heightmap> DifferentiableGeometry for HeightmapPartial<'_, T> {
+    fn du(&self) -> impl DifferentiableGeometry {
+        HeightmapPartial { u: self.u + 1, ..*self }
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        HeightmapPartial { v: self.v + 1, ..*self }
+    }
+}
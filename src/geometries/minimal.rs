@@ -0,0 +1,126 @@
+use nalgebra_glm::{Vec2, Vec3};
+
+use crate::{
+    autodiff::{self, Parametric, Scalar},
+    geometry::{DifferentiableGeometry, Geometry},
+    sdf::SDF,
+};
+
+use super::closest_point::ClosestPoint;
+
+/// A catenary of waist radius c turned around the z axis. u is the angle
+/// around the axis and v the height.
+pub struct Catenoid {
+    pub c: f32,
+}
+
+impl Catenoid {
+    pub fn new(c: f32) -> Self {
+        Self { c }
+    }
+}
+
+impl Parametric for Catenoid {
+    fn point<S: Scalar>(&self, u: S, v: S) -> [S; 3] {
+        let radius = (v / self.c).cosh() * self.c;
+        [radius * u.cos(), radius * u.sin(), v]
+    }
+}
+
+impl Geometry for Catenoid {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        autodiff::evaluate(self, p)
+    }
+}
+
+impl DifferentiableGeometry for Catenoid {
+    fn du(&self) -> impl DifferentiableGeometry {
+        autodiff::du(self)
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        autodiff::dv(self)
+    }
+}
+
+// negative around the z axis
+impl SDF for Catenoid {
+    fn sdf(&self, position: &Vec3) -> f32 {
+        position.xy().norm() - self.c * (position.z / self.c).cosh()
+    }
+}
+
+/// A line through the z axis rising `pitch` per radian as it turns. u is
+/// the angle and v the signed distance from the axis.
+pub struct Helicoid {
+    pub pitch: f32,
+}
+
+impl Helicoid {
+    pub fn new(pitch: f32) -> Self {
+        Self { pitch }
+    }
+}
+
+impl Parametric for Helicoid {
+    fn point<S: Scalar>(&self, u: S, v: S) -> [S; 3] {
+        [v * u.cos(), v * u.sin(), u * self.pitch]
+    }
+}
+
+impl Geometry for Helicoid {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        autodiff::evaluate(self, p)
+    }
+}
+
+impl DifferentiableGeometry for Helicoid {
+    fn du(&self) -> impl DifferentiableGeometry {
+        autodiff::du(self)
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        autodiff::dv(self)
+    }
+}
+
+// zero on the whole helicoid, a distance close to the axis only
+impl SDF for Helicoid {
+    fn sdf(&self, position: &Vec3) -> f32 {
+        let angle = position.z / self.pitch;
+        position.x * angle.sin() - position.y * angle.cos()
+    }
+}
+
+/// Enneper's minimal surface, which intersects itself for |u|, |v| > √3
+pub struct Enneper;
+
+impl Enneper {
+    // an SDF over u, v in [-2, 2]
+    pub fn occluder(self) -> ClosestPoint<Self> {
+        ClosestPoint::new(self, Vec2::repeat(-2.0), Vec2::repeat(2.0), 48)
+    }
+}
+
+impl Parametric for Enneper {
+    fn point<S: Scalar>(&self, u: S, v: S) -> [S; 3] {
+        [
+            u - u * u * u / 3.0 + u * v * v,
+            v - v * v * v / 3.0 + v * u * u,
+            u * u - v * v,
+        ]
+    }
+}
+
+impl Geometry for Enneper {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        autodiff::evaluate(self, p)
+    }
+}
+
+impl DifferentiableGeometry for Enneper {
+    fn du(&self) -> impl DifferentiableGeometry {
+        autodiff::du(self)
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        autodiff::dv(self)
+    }
+}
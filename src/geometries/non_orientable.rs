@@ -0,0 +1,137 @@
+use std::f32::consts::{FRAC_PI_2, PI, SQRT_2, TAU};
+
+use nalgebra_glm::{Vec2, Vec3};
+
+use crate::{
+    autodiff::{self, Parametric, Scalar},
+    geometry::{DifferentiableGeometry, Geometry},
+};
+
+use super::closest_point::ClosestPoint;
+
+// These surfaces have one side only, so they have no inside for an implicit
+// function. Their `occluder` finds distances to the closest point instead.
+
+/// A strip around a circle of `radius` with half a twist. u is the angle in
+/// [0, 2π] and v the signed distance from the center line, up to `width`.
+pub struct MoebiusStrip {
+    pub radius: f32,
+    pub width: f32,
+}
+
+impl MoebiusStrip {
+    pub fn new(radius: f32, width: f32) -> Self {
+        Self { radius, width }
+    }
+
+    pub fn occluder(self) -> ClosestPoint<Self> {
+        let (min, max) = (Vec2::new(0.0, -self.width), Vec2::new(TAU, self.width));
+        ClosestPoint::new(self, min, max, 48)
+    }
+}
+
+impl Parametric for MoebiusStrip {
+    fn point<S: Scalar>(&self, u: S, v: S) -> [S; 3] {
+        let half = u / 2.0;
+        let ring = v * half.cos() + self.radius;
+        [ring * u.cos(), ring * u.sin(), v * half.sin()]
+    }
+}
+
+impl Geometry for MoebiusStrip {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        autodiff::evaluate(self, p)
+    }
+}
+
+impl DifferentiableGeometry for MoebiusStrip {
+    fn du(&self) -> impl DifferentiableGeometry {
+        autodiff::du(self)
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        autodiff::dv(self)
+    }
+}
+
+/// The figure-8 immersion of the Klein bottle, a figure eight swept around
+/// a circle of `radius` with half a twist. u and v are angles in [0, 2π].
+pub struct KleinBottle {
+    pub radius: f32,
+}
+
+impl KleinBottle {
+    pub fn new(radius: f32) -> Self {
+        Self { radius }
+    }
+
+    pub fn occluder(self) -> ClosestPoint<Self> {
+        ClosestPoint::new(self, Vec2::zeros(), Vec2::repeat(TAU), 64)
+    }
+}
+
+impl Parametric for KleinBottle {
+    fn point<S: Scalar>(&self, u: S, v: S) -> [S; 3] {
+        let (half_cos, half_sin) = ((u / 2.0).cos(), (u / 2.0).sin());
+        let (sin_v, sin_2v) = (v.sin(), (v * 2.0).sin());
+        let ring = half_cos * sin_v - half_sin * sin_2v + self.radius;
+        [
+            ring * u.cos(),
+            ring * u.sin(),
+            half_sin * sin_v + half_cos * sin_2v,
+        ]
+    }
+}
+
+impl Geometry for KleinBottle {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        autodiff::evaluate(self, p)
+    }
+}
+
+impl DifferentiableGeometry for KleinBottle {
+    fn du(&self) -> impl DifferentiableGeometry {
+        autodiff::du(self)
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        autodiff::dv(self)
+    }
+}
+
+/// Boy's surface in Apéry's parametrization, an immersion of the projective
+/// plane with u in [-π/2, π/2] and v in [0, π]
+pub struct BoySurface;
+
+impl BoySurface {
+    pub fn occluder(self) -> ClosestPoint<Self> {
+        let (min, max) = (Vec2::new(-FRAC_PI_2, 0.0), Vec2::new(FRAC_PI_2, PI));
+        ClosestPoint::new(self, min, max, 64)
+    }
+}
+
+impl Parametric for BoySurface {
+    fn point<S: Scalar>(&self, u: S, v: S) -> [S; 3] {
+        let cos2_v = v.cos() * v.cos();
+        let sin_2v = (v * 2.0).sin();
+        let scale = ((u * 3.0).sin() * sin_2v * -SQRT_2 + 2.0).recip();
+        [
+            ((u * 2.0).cos() * cos2_v * SQRT_2 + u.cos() * sin_2v) * scale,
+            ((u * 2.0).sin() * cos2_v * SQRT_2 - u.sin() * sin_2v) * scale,
+            cos2_v * scale * 3.0,
+        ]
+    }
+}
+
+impl Geometry for BoySurface {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        autodiff::evaluate(self, p)
+    }
+}
+
+impl DifferentiableGeometry for BoySurface {
+    fn du(&self) -> impl DifferentiableGeometry {
+        autodiff::du(self)
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        autodiff::dv(self)
+    }
+}
@@ -0,0 +1,111 @@
+use std::f32::consts::TAU;
+
+use nalgebra_glm::{Vec2, Vec3};
+
+use crate::{
+    autodiff::{self, Parametric, Scalar},
+    geometry::{DifferentiableGeometry, Geometry},
+    sdf::SDF,
+};
+
+use super::closest_point::ClosestPoint;
+
+// Surfaces of constant negative curvature
+
+/// A tractrix of `radius` turned around the z axis. u is the height
+/// parameter, with the rim at u = 0, and v the angle around the axis.
+pub struct Pseudosphere {
+    pub radius: f32,
+}
+
+impl Pseudosphere {
+    pub fn new(radius: f32) -> Self {
+        Self { radius }
+    }
+}
+
+impl Parametric for Pseudosphere {
+    fn point<S: Scalar>(&self, u: S, v: S) -> [S; 3] {
+        let sech = u.cosh().recip();
+        [
+            sech * v.cos() * self.radius,
+            sech * v.sin() * self.radius,
+            (u - u.tanh()) * self.radius,
+        ]
+    }
+}
+
+impl Geometry for Pseudosphere {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        autodiff::evaluate(self, p)
+    }
+}
+
+impl DifferentiableGeometry for Pseudosphere {
+    fn du(&self) -> impl DifferentiableGeometry {
+        autodiff::du(self)
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        autodiff::dv(self)
+    }
+}
+
+// The height of the tractrix at a distance r from the axis is
+// acosh(1/r) - sqrt(1 - r²). Negative around the axis.
+impl SDF for Pseudosphere {
+    fn sdf(&self, position: &Vec3) -> f32 {
+        let r = position.xy().norm() / self.radius;
+        let z = (position.z / self.radius).abs();
+        let level = if r < 1.0 {
+            let r = r.max(1e-6);
+            z - ((1.0 / r).acosh() - (1.0 - r * r).sqrt())
+        } else {
+            z + r - 1.0
+        };
+        level * self.radius
+    }
+}
+
+/// Dini's twisted pseudosphere. u is the angle around the z axis, rising
+/// `b` per radian, and v in (0, π) runs along the tractrix of radius `a`.
+pub struct Dini {
+    pub a: f32,
+    pub b: f32,
+}
+
+impl Dini {
+    pub fn new(a: f32, b: f32) -> Self {
+        Self { a, b }
+    }
+
+    // an SDF over two turns with v in [0.1, 2]
+    pub fn occluder(self) -> ClosestPoint<Self> {
+        let (min, max) = (Vec2::new(0.0, 0.1), Vec2::new(2.0 * TAU, 2.0));
+        ClosestPoint::new(self, min, max, 64)
+    }
+}
+
+impl Parametric for Dini {
+    fn point<S: Scalar>(&self, u: S, v: S) -> [S; 3] {
+        [
+            u.cos() * v.sin() * self.a,
+            u.sin() * v.sin() * self.a,
+            (v.cos() + (v / 2.0).tan().ln()) * self.a + u * self.b,
+        ]
+    }
+}
+
+impl Geometry for Dini {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        autodiff::evaluate(self, p)
+    }
+}
+
+impl DifferentiableGeometry for Dini {
+    fn du(&self) -> impl DifferentiableGeometry {
+        autodiff::du(self)
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        autodiff::dv(self)
+    }
+}
@@ -1,6 +1,6 @@
-use nalgebra_glm::{Vec2, Vec3};
+use nalgebra_glm::Vec2;
 
-use crate::sdf::SDF;
+use crate::autodiff::{Jet, Scalar};
 
 use super::heightmap::Heightmap;
 
@@ -14,21 +14,33 @@ pub struct Pulse {
     pub t: f32,         // Time
 }
 
-impl Heightmap for Pulse {
-    fn z(&self, p: &Vec2) -> f32 {
-        let r = p.norm();
+impl Pulse {
+    // the height at distance r from the center
+    fn radial<S: Scalar>(&self, r: S) -> S {
         let omega = self.cycles * std::f32::consts::TAU * self.sigma;
         let r0 = -2.0 / self.sigma;
         let rt = r - r0 - self.c * self.t;
-        self.amplitude
-            * (omega * r).sin()
-            * (-self.sigma * self.sigma * rt * rt).exp()
-            * (-self.lambda * r).exp()
+        (r * omega).sin()
+            * (-(rt * rt) * (self.sigma * self.sigma)).exp()
+            * (-r * self.lambda).exp()
+            * self.amplitude
+    }
+
+    fn height<S: Scalar>(&self, u: S, v: S) -> S {
+        self.radial((u * u + v * v).sqrt())
     }
 }
 
-impl SDF for Pulse {
-    fn sdf(&self, position: &Vec3) -> f32 {
-        self.z(&position.xy()) - position.z
+impl Heightmap for Pulse {
+    fn z(&self, p: &Vec2) -> f32 {
+        self.height(p.x, p.y)
+    }
+
+    // The pulse rises from its center with a nonzero slope, a cone point
+    // where it has no derivatives, like `Hole`. Jets there are not finite,
+    // so geodesics and curvature have to keep away from the center.
+    fn jet(&self, p: &Vec2) -> Jet {
+        let (u, v) = Jet::variables(p);
+        self.height(u, v)
     }
 }
@@ -0,0 +1,149 @@
+use nalgebra_glm::{Vec2, Vec3};
+
+use crate::{
+    autodiff::{self, Parametric, Scalar},
+    geometry::{DifferentiableGeometry, Geometry},
+    sdf::SDF,
+};
+
+/// Sphere stretched to the semi-axes a, b and c. u is the angle from the z
+/// axis in [0, π] and v the angle around it, like `Sphere`.
+pub struct Ellipsoid {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+}
+
+impl Ellipsoid {
+    pub fn new(a: f32, b: f32, c: f32) -> Self {
+        Self { a, b, c }
+    }
+}
+
+impl Parametric for Ellipsoid {
+    fn point<S: Scalar>(&self, u: S, v: S) -> [S; 3] {
+        [
+            u.sin() * v.cos() * self.a,
+            u.sin() * v.sin() * self.b,
+            u.cos() * self.c,
+        ]
+    }
+}
+
+impl Geometry for Ellipsoid {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        autodiff::evaluate(self, p)
+    }
+}
+
+impl DifferentiableGeometry for Ellipsoid {
+    fn du(&self) -> impl DifferentiableGeometry {
+        autodiff::du(self)
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        autodiff::dv(self)
+    }
+}
+
+// the unit sphere distance in scaled space, close to the true distance when
+// the axes are similar
+impl SDF for Ellipsoid {
+    fn sdf(&self, position: &Vec3) -> f32 {
+        let scaled = Vec3::new(position.x / self.a, position.y / self.b, position.z / self.c);
+        (scaled.norm() - 1.0) * self.a.min(self.b).min(self.c)
+    }
+}
+
+/// x²/a² + y²/b² - z²/c² = 1, with u the height parameter and v the angle
+/// around the z axis
+pub struct HyperboloidOneSheet {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+}
+
+impl HyperboloidOneSheet {
+    pub fn new(a: f32, b: f32, c: f32) -> Self {
+        Self { a, b, c }
+    }
+}
+
+impl Parametric for HyperboloidOneSheet {
+    fn point<S: Scalar>(&self, u: S, v: S) -> [S; 3] {
+        [
+            u.cosh() * v.cos() * self.a,
+            u.cosh() * v.sin() * self.b,
+            u.sinh() * self.c,
+        ]
+    }
+}
+
+impl Geometry for HyperboloidOneSheet {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        autodiff::evaluate(self, p)
+    }
+}
+
+impl DifferentiableGeometry for HyperboloidOneSheet {
+    fn du(&self) -> impl DifferentiableGeometry {
+        autodiff::du(self)
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        autodiff::dv(self)
+    }
+}
+
+// negative around the z axis
+impl SDF for HyperboloidOneSheet {
+    fn sdf(&self, position: &Vec3) -> f32 {
+        let (x, y, z) = (position.x / self.a, position.y / self.b, position.z / self.c);
+        (x * x + y * y).sqrt() - (1.0 + z * z).sqrt()
+    }
+}
+
+/// The upper sheet of z²/c² - x²/a² - y²/b² = 1, with u ≥ 0 the distance
+/// parameter from the vertex and v the angle around the z axis
+pub struct HyperboloidTwoSheets {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+}
+
+impl HyperboloidTwoSheets {
+    pub fn new(a: f32, b: f32, c: f32) -> Self {
+        Self { a, b, c }
+    }
+}
+
+impl Parametric for HyperboloidTwoSheets {
+    fn point<S: Scalar>(&self, u: S, v: S) -> [S; 3] {
+        [
+            u.sinh() * v.cos() * self.a,
+            u.sinh() * v.sin() * self.b,
+            u.cosh() * self.c,
+        ]
+    }
+}
+
+impl Geometry for HyperboloidTwoSheets {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        autodiff::evaluate(self, p)
+    }
+}
+
+impl DifferentiableGeometry for HyperboloidTwoSheets {
+    fn du(&self) -> impl DifferentiableGeometry {
+        autodiff::du(self)
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        autodiff::dv(self)
+    }
+}
+
+// only the upper sheet, negative above it
+impl SDF for HyperboloidTwoSheets {
+    fn sdf(&self, position: &Vec3) -> f32 {
+        let (x, y) = (position.x / self.a, position.y / self.b);
+        ((1.0 + x * x + y * y).sqrt() - position.z / self.c) * self.c
+    }
+}
@@ -0,0 +1,52 @@
+use nalgebra_glm::Vec2;
+
+use crate::autodiff::{Jet, Scalar};
+
+use super::heightmap::Heightmap;
+
+/// z = u²/a² - v²/b²
+pub struct HyperbolicParaboloid {
+    pub a: f32,
+    pub b: f32,
+}
+
+impl HyperbolicParaboloid {
+    pub fn new(a: f32, b: f32) -> Self {
+        Self { a, b }
+    }
+
+    fn height<S: Scalar>(&self, u: S, v: S) -> S {
+        u * u / (self.a * self.a) - v * v / (self.b * self.b)
+    }
+}
+
+impl Heightmap for HyperbolicParaboloid {
+    fn z(&self, p: &Vec2) -> f32 {
+        self.height(p.x, p.y)
+    }
+
+    fn jet(&self, p: &Vec2) -> Jet {
+        let (u, v) = Jet::variables(p);
+        self.height(u, v)
+    }
+}
+
+/// z = u³ - 3uv², three valleys for the legs and one for the tail
+pub struct MonkeySaddle;
+
+impl MonkeySaddle {
+    fn height<S: Scalar>(&self, u: S, v: S) -> S {
+        u * u * u - u * v * v * 3.0
+    }
+}
+
+impl Heightmap for MonkeySaddle {
+    fn z(&self, p: &Vec2) -> f32 {
+        self.height(p.x, p.y)
+    }
+
+    fn jet(&self, p: &Vec2) -> Jet {
+        let (u, v) = Jet::variables(p);
+        self.height(u, v)
+    }
+}
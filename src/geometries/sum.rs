@@ -1,6 +1,6 @@
-use nalgebra_glm::{Vec2, Vec3};
+use nalgebra_glm::Vec2;
 
-use crate::sdf::SDF;
+use crate::autodiff::Jet;
 
 use super::heightmap::Heightmap;
 
@@ -23,14 +23,8 @@ where
     fn z(&self, p: &Vec2) -> f32 {
         self.a.z(p) + self.b.z(p)
     }
-}
 
-impl<A, B> SDF for Sum<A, B>
-where
-    A: Heightmap,
-    B: Heightmap,
-{
-    fn sdf(&self, position: &Vec3) -> f32 {
-        self.z(&position.xy()) - position.z
+    fn jet(&self, p: &Vec2) -> Jet {
+        self.a.jet(p) + self.b.jet(p)
     }
 }
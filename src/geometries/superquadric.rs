@@ -0,0 +1,70 @@
+use nalgebra_glm::{Vec2, Vec3};
+
+use crate::{
+    autodiff::{self, Parametric, Scalar},
+    geometry::{DifferentiableGeometry, Geometry},
+    sdf::SDF,
+};
+
+/// A superellipsoid with semi-axes a, b and c. `east_west` and
+/// `north_south` shape the cross sections: 1 is round, towards 0 is boxy
+/// and 2 is a diamond. u is the latitude in [-π/2, π/2] and v the
+/// longitude in [-π, π].
+pub struct Superquadric {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub east_west: f32,
+    pub north_south: f32,
+}
+
+impl Superquadric {
+    pub fn new(a: f32, b: f32, c: f32, east_west: f32, north_south: f32) -> Self {
+        Self { a, b, c, east_west, north_south }
+    }
+}
+
+// |x|^e with the sign of x
+fn signed_pow<S: Scalar>(x: S, e: f32) -> S {
+    let sign = if x.value() < 0.0 { -1.0 } else { 1.0 };
+    x.abs().powf(e) * sign
+}
+
+impl Parametric for Superquadric {
+    fn point<S: Scalar>(&self, u: S, v: S) -> [S; 3] {
+        let ring = signed_pow(u.cos(), self.north_south);
+        [
+            ring * signed_pow(v.cos(), self.east_west) * self.a,
+            ring * signed_pow(v.sin(), self.east_west) * self.b,
+            signed_pow(u.sin(), self.north_south) * self.c,
+        ]
+    }
+}
+
+impl Geometry for Superquadric {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        autodiff::evaluate(self, p)
+    }
+}
+
+impl DifferentiableGeometry for Superquadric {
+    fn du(&self) -> impl DifferentiableGeometry {
+        autodiff::du(self)
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        autodiff::dv(self)
+    }
+}
+
+// The inside-outside function, taken to the power that makes it grow
+// linearly with the distance from the center
+impl SDF for Superquadric {
+    fn sdf(&self, position: &Vec3) -> f32 {
+        let (e, n) = (self.east_west, self.north_south);
+        let x = (position.x / self.a).abs().powf(2.0 / e);
+        let y = (position.y / self.b).abs().powf(2.0 / e);
+        let z = (position.z / self.c).abs().powf(2.0 / n);
+        let level = (x + y).powf(e / n) + z;
+        (level.powf(0.5 * n) - 1.0) * self.a.min(self.b).min(self.c)
+    }
+}
@@ -0,0 +1,91 @@
+use nalgebra_glm::{diagonal3x3, quat_normalize, quat_to_mat3, Mat3, Quat, Vec2, Vec3};
+
+use crate::{
+    geometry::{DifferentiableGeometry, Geometry},
+    sdf::SDF,
+};
+
+/// A geometry scaled, then rotated, then translated. Scale factors may
+/// differ per axis but must not be zero.
+pub struct Transformed<G> {
+    pub geometry: G,
+    pub translation: Vec3,
+    // orthonormal
+    pub rotation: Mat3,
+    pub scale: Vec3,
+}
+
+impl<G> Transformed<G> {
+    pub fn new(geometry: G) -> Self {
+        Self {
+            geometry,
+            translation: Vec3::zeros(),
+            rotation: Mat3::identity(),
+            scale: Vec3::repeat(1.0),
+        }
+    }
+
+    pub fn with_translation(self, translation: Vec3) -> Self {
+        Self { translation, ..self }
+    }
+
+    pub fn with_rotation(self, rotation: &Quat) -> Self {
+        Self { rotation: quat_to_mat3(&quat_normalize(rotation)), ..self }
+    }
+
+    pub fn with_rotation_matrix(self, rotation: Mat3) -> Self {
+        Self { rotation, ..self }
+    }
+
+    pub fn with_scale(self, scale: Vec3) -> Self {
+        Self { scale, ..self }
+    }
+
+    pub fn with_uniform_scale(self, scale: f32) -> Self {
+        self.with_scale(Vec3::repeat(scale))
+    }
+
+    // rotation times scale, all that is left of the transform in derivatives
+    fn linear(&self) -> Mat3 {
+        self.rotation * diagonal3x3(&self.scale)
+    }
+
+    // the same rotation and scale without translation
+    fn derivative<D>(&self, geometry: D) -> Transformed<D> {
+        Transformed {
+            geometry,
+            translation: Vec3::zeros(),
+            rotation: self.rotation,
+            scale: self.scale,
+        }
+    }
+}
+
+impl<G: Geometry> Geometry for Transformed<G> {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        self.linear() * self.geometry.evaluate(p) + self.translation
+    }
+}
+
+impl<G: DifferentiableGeometry> DifferentiableGeometry for Transformed<G> {
+    fn du(&self) -> impl DifferentiableGeometry {
+        self.derivative(self.geometry.du())
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        self.derivative(self.geometry.dv())
+    }
+}
+
+// Distances shrink by at most the smallest scale factor, so scaling the
+// distance of the untransformed position by it stays a lower bound. It is
+// exact for uniform scales.
+impl<G: SDF> SDF for Transformed<G> {
+    fn sdf(&self, position: &Vec3) -> f32 {
+        let local =
+            (self.rotation.transpose() * (position - self.translation)).component_div(&self.scale);
+        self.geometry.sdf(&local) * self.scale.abs().min()
+    }
+    fn is_distance_bound(&self) -> bool {
+        self.geometry.is_distance_bound()
+    }
+}
@@ -0,0 +1,266 @@
+use std::{
+    collections::VecDeque,
+    io::{self, ErrorKind, Read, Write},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    gcode::{gcode_lines, GcodeOptions},
+    paper::Paper,
+};
+
+/// Size of the serial receive buffer on stock GRBL firmware
+pub const RX_BUFFER_SIZE: usize = 128;
+
+// realtime commands are handled by GRBL as soon as they are received
+const FEED_HOLD: u8 = b'!';
+const CYCLE_START: u8 = b'~';
+const SOFT_RESET: u8 = 0x18;
+const STATUS_REPORT: u8 = b'?';
+
+#[derive(Default)]
+struct ControlState {
+    paused: bool,
+    cancelled: bool,
+    realtime: VecDeque<u8>,
+}
+
+/// Handle to pause, resume or cancel a running job. Clones share state so
+/// one can be moved to another thread or into the progress callback.
+#[derive(Clone, Default)]
+pub struct Control {
+    state: Arc<Mutex<ControlState>>,
+}
+
+impl Control {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // stops sending lines and issues a feed hold
+    pub fn pause(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.paused = true;
+        state.realtime.push_back(FEED_HOLD);
+    }
+
+    // continues streaming, also releases a hold from an M0 in the program
+    pub fn resume(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.paused = false;
+        state.realtime.push_back(CYCLE_START);
+    }
+
+    // aborts the job with a soft reset
+    pub fn cancel(&self) {
+        self.state.lock().unwrap().cancelled = true;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state.lock().unwrap().paused
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.lock().unwrap().cancelled
+    }
+
+    fn take_realtime(&self) -> Vec<u8> {
+        self.state.lock().unwrap().realtime.drain(..).collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Progress {
+    pub sent: usize,
+    pub acknowledged: usize,
+    pub total: usize,
+}
+
+impl Progress {
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.acknowledged as f32 / self.total as f32
+        }
+    }
+}
+
+/// Streams G-code to a GRBL controller using character-counting flow control:
+/// lines are sent as long as the bytes not yet acknowledged with `ok` fit in
+/// the controller's receive buffer.
+pub struct Driver<T: Read + Write> {
+    transport: T,
+    pub rx_buffer_size: usize,
+    // how long to wait for a response before giving up
+    pub timeout: Duration,
+    // how often to ask for a status report while waiting. A report counts as
+    // a response, so a controller holding at an M0 does not time out.
+    pub status_interval: Duration,
+    // sleep between polls when the transport has nothing to read
+    pub poll_interval: Duration,
+    in_flight: VecDeque<usize>,
+    received: Vec<u8>,
+}
+
+fn is_idle(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted
+    )
+}
+
+impl<T: Read + Write> Driver<T> {
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            rx_buffer_size: RX_BUFFER_SIZE,
+            timeout: Duration::from_secs(60),
+            status_interval: Duration::from_secs(1),
+            poll_interval: Duration::from_millis(1),
+            in_flight: VecDeque::new(),
+            received: Vec::new(),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+
+    // returns a complete response line if one is available without blocking
+    fn poll_line(&mut self) -> io::Result<Option<String>> {
+        loop {
+            if let Some(end) = self.received.iter().position(|&byte| byte == b'\n') {
+                let line: Vec<u8> = self.received.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line).trim().to_string();
+                if line.is_empty() {
+                    continue;
+                }
+                return Ok(Some(line));
+            }
+            let mut buffer = [0; 256];
+            match self.transport.read(&mut buffer) {
+                Ok(0) => return Ok(None),
+                Ok(n) => self.received.extend_from_slice(&buffer[..n]),
+                Err(error) if is_idle(&error) => return Ok(None),
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    fn in_flight_bytes(&self) -> usize {
+        self.in_flight.iter().sum()
+    }
+
+    /// Requests a status report, e.g. `<Idle|MPos:0.000,0.000,0.000|FS:0,0>`
+    pub fn status(&mut self) -> io::Result<String> {
+        self.transport.write_all(&[STATUS_REPORT])?;
+        self.transport.flush()?;
+        let started = Instant::now();
+        loop {
+            match self.poll_line()? {
+                Some(line) if line.starts_with('<') => return Ok(line),
+                Some(_) => {}
+                None if started.elapsed() > self.timeout => {
+                    return Err(io::Error::new(ErrorKind::TimedOut, "no status report"));
+                }
+                None => thread::sleep(self.poll_interval),
+            }
+        }
+    }
+
+    /// Streams the lines and blocks until all of them are acknowledged. The
+    /// callback is invoked after every acknowledged line.
+    pub fn stream(
+        &mut self,
+        lines: &[String],
+        control: &Control,
+        mut on_progress: impl FnMut(&Progress),
+    ) -> io::Result<Progress> {
+        let mut progress = Progress { sent: 0, acknowledged: 0, total: lines.len() };
+        let mut last_response = Instant::now();
+        let mut last_status = Instant::now();
+
+        while progress.acknowledged < progress.total {
+            if control.is_cancelled() {
+                self.transport.write_all(&[SOFT_RESET])?;
+                self.transport.flush()?;
+                self.in_flight.clear();
+                return Err(io::Error::new(ErrorKind::Interrupted, "job cancelled"));
+            }
+            let realtime = control.take_realtime();
+            if !realtime.is_empty() {
+                self.transport.write_all(&realtime)?;
+            }
+
+            // fill the receive buffer
+            while !control.is_paused() && progress.sent < progress.total {
+                let line = lines[progress.sent].trim();
+                let size = line.len() + 1;
+                if size > self.rx_buffer_size {
+                    return Err(io::Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("line {} does not fit the receive buffer", progress.sent + 1),
+                    ));
+                }
+                if self.in_flight_bytes() + size > self.rx_buffer_size {
+                    break;
+                }
+                self.transport.write_all(line.as_bytes())?;
+                self.transport.write_all(b"\n")?;
+                self.in_flight.push_back(size);
+                progress.sent += 1;
+            }
+            self.transport.flush()?;
+
+            match self.poll_line()? {
+                Some(line) if line == "ok" => {
+                    self.in_flight.pop_front();
+                    progress.acknowledged += 1;
+                    last_response = Instant::now();
+                    on_progress(&progress);
+                }
+                Some(line) if line.starts_with("error:") => {
+                    let number = progress.acknowledged + 1;
+                    return Err(io::Error::new(
+                        ErrorKind::InvalidData,
+                        format!("line {number} `{}` failed with {line}", lines[number - 1].trim()),
+                    ));
+                }
+                Some(line) if line.starts_with("ALARM:") => {
+                    return Err(io::Error::other(line));
+                }
+                Some(line) if line.starts_with('<') => last_response = Instant::now(),
+                // welcome message and feedback
+                Some(_) => {}
+                None => {
+                    if control.is_paused() {
+                        last_response = Instant::now();
+                    } else if last_response.elapsed() > self.timeout {
+                        return Err(io::Error::new(ErrorKind::TimedOut, "no response from GRBL"));
+                    }
+                    if last_response.elapsed().min(last_status.elapsed()) > self.status_interval {
+                        self.transport.write_all(&[STATUS_REPORT])?;
+                        last_status = Instant::now();
+                    }
+                    thread::sleep(self.poll_interval);
+                }
+            }
+        }
+        Ok(progress)
+    }
+
+    /// Converts the paper to G-code and streams it
+    pub fn plot(
+        &mut self,
+        paper: &Paper,
+        options: &GcodeOptions,
+        control: &Control,
+        on_progress: impl FnMut(&Progress),
+    ) -> io::Result<Progress> {
+        let lines = gcode_lines(paper, options);
+        self.stream(&lines, control, on_progress)
+    }
+}
@@ -0,0 +1,366 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+    time::Duration,
+};
+
+use nalgebra_glm::Vec3;
+
+use super::driver::RX_BUFFER_SIZE;
+use crate::gcode::PenLift;
+
+const WELCOME: &str = "Grbl 1.1h ['$' for help]";
+
+// lines GRBL accepts ahead of the motion being executed
+const PLANNER_BLOCKS: usize = 16;
+
+// GRBL error codes
+const EXPECTED_COMMAND_LETTER: u32 = 1;
+const BAD_NUMBER_FORMAT: u32 = 2;
+const UNSUPPORTED_COMMAND: u32 = 20;
+const UNDEFINED_FEED_RATE: u32 = 22;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum State {
+    Idle,
+    Run,
+    Hold,
+}
+
+/// An in-process stand-in for a GRBL controller. It answers `ok`/`error:`
+/// like the firmware, reports status and keeps track of where the pen went,
+/// so jobs can be tested and dry-run without hardware.
+///
+/// Lines are parsed one at a time when the host reads, so a host that
+/// ignores flow control overflows the receive buffer like on the real device.
+/// During a feed hold or an M0, lines are acknowledged into the planner until
+/// it is full and only executed after a cycle start.
+pub struct Simulator {
+    rx_buffer_size: usize,
+    received: Vec<u8>,
+    output: VecDeque<u8>,
+    overflowed: bool,
+    held: bool,
+    // lines acknowledged during a hold, waiting to be executed
+    planned: VecDeque<String>,
+    // modal state
+    position: Vec3,
+    relative: bool,
+    millimetres: bool,
+    motion: u32,
+    feed: f32,
+    spindle: f32,
+    spindle_on: bool,
+    // feed rate used for G0
+    pub rapid_rate: f32,
+    // decides when the pen touches the paper
+    pub pen_lift: PenLift,
+    // statistics
+    lines: usize,
+    draw_length: f32,
+    move_length: f32,
+    elapsed: Duration,
+}
+
+impl Default for Simulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn strip_comments(line: &str) -> String {
+    let mut stripped = String::new();
+    let mut in_comment = false;
+    for c in line.chars() {
+        match c {
+            '(' => in_comment = true,
+            ')' => in_comment = false,
+            ';' if !in_comment => break,
+            c if !in_comment && !c.is_whitespace() => stripped.push(c.to_ascii_uppercase()),
+            _ => {}
+        }
+    }
+    stripped
+}
+
+fn parse_words(line: &str) -> Result<Vec<(char, f32)>, u32> {
+    let mut words = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(letter) = chars.next() {
+        if !letter.is_ascii_alphabetic() {
+            return Err(EXPECTED_COMMAND_LETTER);
+        }
+        let mut number = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_ascii_digit() || c == '.' || c == '-' || c == '+' {
+                number.push(c);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        let value = number.parse::<f32>().map_err(|_| BAD_NUMBER_FORMAT)?;
+        words.push((letter, value));
+    }
+    Ok(words)
+}
+
+impl Simulator {
+    pub fn new() -> Self {
+        let mut simulator = Self {
+            rx_buffer_size: RX_BUFFER_SIZE,
+            received: Vec::new(),
+            output: VecDeque::new(),
+            overflowed: false,
+            held: false,
+            planned: VecDeque::new(),
+            position: Vec3::zeros(),
+            relative: false,
+            millimetres: true,
+            motion: 0,
+            feed: 0.0,
+            spindle: 0.0,
+            spindle_on: false,
+            rapid_rate: 8000.0,
+            pen_lift: PenLift::Servo { power: 1000, dwell: 0.15 },
+            lines: 0,
+            draw_length: 0.0,
+            move_length: 0.0,
+            elapsed: Duration::ZERO,
+        };
+        simulator.respond(WELCOME);
+        simulator
+    }
+
+    pub fn with_pen_lift(pen_lift: PenLift) -> Self {
+        Self { pen_lift, ..Self::new() }
+    }
+
+    pub fn state(&self) -> State {
+        if self.held {
+            State::Hold
+        } else if !self.planned.is_empty() || self.received.contains(&b'\n') {
+            State::Run
+        } else {
+            State::Idle
+        }
+    }
+
+    pub fn position(&self) -> Vec3 {
+        self.position
+    }
+
+    pub fn pen_down(&self) -> bool {
+        self.pen_down_at(&self.position)
+    }
+
+    fn pen_down_at(&self, position: &Vec3) -> bool {
+        match self.pen_lift {
+            PenLift::Servo { .. } => self.spindle_on,
+            // within 0.1 mm of `down`, so the short lift between dots is up
+            PenLift::Z { down, up, .. } => (position.z - down) * (up - down).signum() < 0.1,
+        }
+    }
+
+    // true if the host sent more than the receive buffer could hold
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    // number of lines parsed so far
+    pub fn lines(&self) -> usize {
+        self.lines
+    }
+
+    // drawing and moving distance in mm
+    pub fn length(&self) -> (f32, f32) {
+        (self.draw_length, self.move_length)
+    }
+
+    // time the job would take at the programmed feed rates, ignoring acceleration
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    fn respond(&mut self, message: &str) {
+        self.output.extend(message.as_bytes());
+        self.output.extend(b"\r\n");
+    }
+
+    fn status_report(&mut self) {
+        let state = match self.state() {
+            State::Idle => "Idle",
+            State::Run => "Run",
+            State::Hold => "Hold:0",
+        };
+        let p = self.position;
+        let report = format!(
+            "<{state}|MPos:{:.3},{:.3},{:.3}|FS:{},{}>",
+            p.x, p.y, p.z, self.feed, self.spindle
+        );
+        self.respond(&report);
+    }
+
+    fn soft_reset(&mut self) {
+        self.received.clear();
+        self.planned.clear();
+        self.output.clear();
+        self.held = false;
+        self.spindle_on = false;
+        self.respond(WELCOME);
+    }
+
+    fn realtime(&mut self, byte: u8) -> bool {
+        match byte {
+            b'?' => self.status_report(),
+            b'!' => self.held = true,
+            b'~' => self.cycle_start(),
+            0x18 => self.soft_reset(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn execute(&mut self, line: &str) -> Result<(), u32> {
+        let line = strip_comments(line);
+        if line.is_empty() || line.starts_with('$') {
+            return Ok(());
+        }
+
+        let mut target = if self.relative {
+            Vec3::zeros()
+        } else {
+            self.position
+        };
+        let mut has_axis = false;
+        let mut dwell = 0.0;
+        let mut dwell_command = false;
+        let scale = if self.millimetres { 1.0 } else { 25.4 };
+        for (letter, value) in parse_words(&line)? {
+            match letter {
+                'G' => match value as u32 {
+                    0 | 1 => self.motion = value as u32,
+                    4 => dwell_command = true,
+                    20 => self.millimetres = false,
+                    21 => self.millimetres = true,
+                    90 => self.relative = false,
+                    91 => self.relative = true,
+                    _ => return Err(UNSUPPORTED_COMMAND),
+                },
+                'M' => match value as u32 {
+                    0 => self.held = true,
+                    2 | 30 => self.spindle_on = false,
+                    3 => self.spindle_on = true,
+                    5 => self.spindle_on = false,
+                    _ => return Err(UNSUPPORTED_COMMAND),
+                },
+                'X' | 'Y' | 'Z' => {
+                    let axis = (letter as u8 - b'X') as usize;
+                    target[axis] = value * scale;
+                    has_axis = true;
+                }
+                'F' => self.feed = value * scale,
+                'S' => self.spindle = value,
+                'P' => dwell = value,
+                _ => return Err(UNSUPPORTED_COMMAND),
+            }
+        }
+
+        if dwell_command {
+            self.elapsed += Duration::from_secs_f32(dwell.max(0.0));
+        }
+        if has_axis {
+            if self.relative {
+                target += self.position;
+            }
+            let feed = if self.motion == 0 {
+                self.rapid_rate
+            } else {
+                self.feed
+            };
+            if feed <= 0.0 {
+                return Err(UNDEFINED_FEED_RATE);
+            }
+            let distance = (target.xy() - self.position.xy()).norm();
+            let travel = (target - self.position).norm();
+            if self.pen_down() && self.pen_down_at(&target) {
+                self.draw_length += distance;
+            } else {
+                self.move_length += distance;
+            }
+            self.elapsed += Duration::from_secs_f32(60.0 * travel / feed);
+            self.position = target;
+        }
+        Ok(())
+    }
+
+    // releases a hold and executes what was planned meanwhile, up to the
+    // next M0
+    fn cycle_start(&mut self) {
+        self.held = false;
+        while !self.held {
+            let Some(line) = self.planned.pop_front() else {
+                break;
+            };
+            if let Err(code) = self.execute(&line) {
+                self.respond(&format!("error:{code}"));
+            }
+        }
+    }
+
+    // parses the next complete line in the receive buffer, if any
+    fn process_line(&mut self) {
+        if self.held && self.planned.len() >= PLANNER_BLOCKS {
+            return;
+        }
+        let Some(end) = self.received.iter().position(|&byte| byte == b'\n') else {
+            return;
+        };
+        let line: Vec<u8> = self.received.drain(..=end).collect();
+        let line = String::from_utf8_lossy(&line).to_string();
+        self.lines += 1;
+        if self.held {
+            self.planned.push_back(line);
+            self.respond("ok");
+            return;
+        }
+        match self.execute(&line) {
+            Ok(()) => self.respond("ok"),
+            Err(code) => self.respond(&format!("error:{code}")),
+        }
+    }
+}
+
+impl Write for Simulator {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &byte in buf {
+            if self.realtime(byte) {
+                continue;
+            }
+            // like the firmware, bytes that do not fit are lost
+            if self.received.len() >= self.rx_buffer_size {
+                self.overflowed = true;
+                continue;
+            }
+            self.received.push(byte);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Read for Simulator {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.output.is_empty() {
+            self.process_line();
+        }
+        let n = buf.len().min(self.output.len());
+        for (slot, byte) in buf.iter_mut().zip(self.output.drain(..n)) {
+            *slot = byte;
+        }
+        Ok(n)
+    }
+}
@@ -9,6 +9,7 @@ pub fn grid_line(start: Vec2, end: Vec2, n: usize) -> Polyline2 {
             .map(|i| i as f32 / (n - 1) as f32)
             .map(|t| (1.0 - t) * start + t * end)
             .collect(),
+        closed: false,
     }
 }
 
@@ -0,0 +1,231 @@
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+use nalgebra_glm::{rotate_vec2, Vec2};
+
+use crate::polyline::Polyline2;
+
+/// Decides which parts of overlapping outlines are filled
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FillRule {
+    // holes are outlines inside an odd number of others
+    EvenOdd,
+    // holes need to wind the other way than their outer outline
+    NonZero,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct HatchOptions {
+    // radians, 0 draws lines along x
+    pub angle: f32,
+    // mm between lines
+    pub spacing: f32,
+    pub rule: FillRule,
+    // adds a second pass at right angles
+    pub cross: bool,
+    // connects neighbouring lines into one stroke where it stays inside
+    pub zigzag: bool,
+}
+
+impl Default for HatchOptions {
+    fn default() -> Self {
+        Self {
+            angle: FRAC_PI_4,
+            spacing: 1.0,
+            rule: FillRule::EvenOdd,
+            cross: false,
+            zigzag: false,
+        }
+    }
+}
+
+impl HatchOptions {
+    // lines one pen width apart so they touch and cover the area
+    pub fn solid(pen: f32) -> HatchOptions {
+        HatchOptions { spacing: pen, zigzag: true, ..HatchOptions::default() }
+    }
+}
+
+// edges of all outlines, closed or not
+fn edges(outlines: &[Polyline2]) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    outlines.iter().filter(|o| o.points.len() > 2).flat_map(|outline| {
+        let n = outline.points.len();
+        (0..n).map(move |i| (outline.points[i], outline.points[(i + 1) % n]))
+    })
+}
+
+fn filled(rule: FillRule, winding: i32) -> bool {
+    match rule {
+        FillRule::EvenOdd => winding % 2 != 0,
+        FillRule::NonZero => winding != 0,
+    }
+}
+
+fn inside(point: &Vec2, outlines: &[Polyline2], rule: FillRule) -> bool {
+    let mut winding = 0;
+    for (a, b) in edges(outlines) {
+        if (a.y <= point.y) != (b.y <= point.y) {
+            let x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if point.x < x {
+                winding += if b.y > a.y { 1 } else { -1 };
+            }
+        }
+    }
+    filled(rule, winding)
+}
+
+// true if the segment crosses an outline between its endpoints
+fn crosses(p: &Vec2, q: &Vec2, outlines: &[Polyline2]) -> bool {
+    let cross = |a: &Vec2, b: &Vec2| a.x * b.y - a.y * b.x;
+    let d = q - p;
+    edges(outlines).any(|(c, e)| {
+        let e = e - c;
+        let denominator = cross(&d, &e);
+        if denominator.abs() < f32::EPSILON {
+            return false;
+        }
+        let pc = c - p;
+        let t = cross(&pc, &e) / denominator;
+        let s = cross(&pc, &d) / denominator;
+        (1e-4..=1.0 - 1e-4).contains(&t) && (0.0..=1.0).contains(&s)
+    })
+}
+
+// filled intervals of each scanline, in a frame where the lines run along x
+fn scanlines(outlines: &[Polyline2], spacing: f32, rule: FillRule) -> Vec<(f32, Vec<(f32, f32)>)> {
+    let Some((min, max)) =
+        edges(outlines).map(|(a, _)| a.y).fold(None, |range: Option<(f32, f32)>, y| {
+            Some(range.map_or((y, y), |(min, max)| (min.min(y), max.max(y))))
+        })
+    else {
+        return Vec::new();
+    };
+
+    // lines sit halfway between multiples of the spacing so neighbouring
+    // shapes line up and no line runs along a horizontal edge
+    let first = (min / spacing - 0.5).ceil() as i64;
+    let last = (max / spacing - 0.5).floor() as i64;
+    (first..=last)
+        .map(|k| {
+            let y = (k as f32 + 0.5) * spacing;
+            let mut crossings: Vec<(f32, i32)> = edges(outlines)
+                .filter(|(a, b)| (a.y <= y) != (b.y <= y))
+                .map(|(a, b)| {
+                    let x = a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x);
+                    (x, if b.y > a.y { 1 } else { -1 })
+                })
+                .collect();
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut spans = Vec::new();
+            let mut winding = 0;
+            let mut start = 0.0;
+            for (x, direction) in crossings {
+                let was_filled = filled(rule, winding);
+                winding += direction;
+                match (was_filled, filled(rule, winding)) {
+                    (false, true) => start = x,
+                    (true, false) if x > start => spans.push((start, x)),
+                    _ => {}
+                }
+            }
+            (y, spans)
+        })
+        .collect()
+}
+
+// Joins spans of consecutive scanlines into zig-zag strokes. A stroke runs
+// along a span, steps to an overlapping span of the next line at the same
+// side and comes back the other way.
+fn zigzag(
+    lines: &[(f32, Vec<(f32, f32)>)],
+    outlines: &[Polyline2],
+    options: &HatchOptions,
+) -> Vec<Polyline2> {
+    let mut used: Vec<Vec<bool>> =
+        lines.iter().map(|(_, spans)| vec![false; spans.len()]).collect();
+    let mut strokes = Vec::new();
+    for row in 0..lines.len() {
+        for index in 0..lines[row].1.len() {
+            if used[row][index] {
+                continue;
+            }
+            let mut stroke = Polyline2::new();
+            let (mut row, mut index, mut rightwards) = (row, index, true);
+            loop {
+                used[row][index] = true;
+                let (y, (x0, x1)) = (lines[row].0, lines[row].1[index]);
+                let (from, to) = if rightwards { (x0, x1) } else { (x1, x0) };
+                stroke.add(Vec2::new(from, y));
+                stroke.add(Vec2::new(to, y));
+
+                let end = Vec2::new(to, y);
+                // steps often run along the outline, test a little inside
+                let inwards =
+                    Vec2::new(if rightwards { -0.01 } else { 0.01 } * options.spacing, 0.0);
+                let next = lines.get(row + 1).and_then(|(next_y, spans)| {
+                    spans
+                        .iter()
+                        .enumerate()
+                        .filter(|&(i, &(a, b))| !used[row + 1][i] && a < x1 && b > x0)
+                        .map(|(i, &(a, b))| (i, Vec2::new(if rightwards { b } else { a }, *next_y)))
+                        .filter(|(_, start)| {
+                            !crosses(&end, start, outlines)
+                                && inside(&(end.lerp(start, 0.5) + inwards), outlines, options.rule)
+                        })
+                        .min_by(|a, b| (a.1 - end).norm().total_cmp(&(b.1 - end).norm()))
+                });
+                match next {
+                    Some((i, _)) => (row, index, rightwards) = (row + 1, i, !rightwards),
+                    None => break,
+                }
+            }
+            strokes.push(stroke);
+        }
+    }
+    strokes
+}
+
+fn hatch_at(outlines: &[Polyline2], angle: f32, options: &HatchOptions) -> Vec<Polyline2> {
+    // rotate so the hatch lines run along x, then back
+    let rotated: Vec<Polyline2> = outlines
+        .iter()
+        .map(|outline| Polyline2 {
+            points: outline.points.iter().map(|p| rotate_vec2(p, -angle)).collect(),
+            closed: true,
+        })
+        .collect();
+    let lines = scanlines(&rotated, options.spacing, options.rule);
+    let strokes = if options.zigzag {
+        zigzag(&lines, &rotated, options)
+    } else {
+        lines
+            .iter()
+            .flat_map(|(y, spans)| {
+                spans.iter().map(move |&(x0, x1)| Polyline2 {
+                    points: vec![Vec2::new(x0, *y), Vec2::new(x1, *y)],
+                    closed: false,
+                })
+            })
+            .collect()
+    };
+    strokes
+        .into_iter()
+        .map(|stroke| Polyline2 {
+            points: stroke.points.iter().map(|p| rotate_vec2(p, angle)).collect(),
+            closed: false,
+        })
+        .collect()
+}
+
+/// Fills the area enclosed by the outlines with parallel lines. Outlines are
+/// treated as closed and may contain holes as decided by the fill rule.
+pub fn hatch(outlines: &[Polyline2], options: &HatchOptions) -> Vec<Polyline2> {
+    if options.spacing <= 0.0 {
+        return Vec::new();
+    }
+    let mut strokes = hatch_at(outlines, options.angle, options);
+    if options.cross {
+        strokes.extend(hatch_at(outlines, options.angle + FRAC_PI_2, options));
+    }
+    strokes
+}
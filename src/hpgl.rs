@@ -0,0 +1,96 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+use nalgebra_glm::Vec2;
+
+use crate::{paper::Paper, view_box::ViewBox};
+
+/// HPGL plotter units per millimetre (one unit is 0.025 mm)
+pub const UNITS_PER_MM: f32 = 40.0;
+
+pub struct HpglOptions {
+    // SP pen number for each layer, layers without an entry use index + 1
+    pub pens: Vec<u32>,
+    // hardware clip window (IW) in paper millimetres
+    pub clip: Option<ViewBox>,
+    // HPGL has y pointing up while SVG has y pointing down
+    pub flip_y: bool,
+}
+
+impl Default for HpglOptions {
+    fn default() -> Self {
+        Self { pens: Vec::new(), clip: None, flip_y: true }
+    }
+}
+
+impl HpglOptions {
+    fn pen(&self, layer: usize) -> u32 {
+        self.pens.get(layer).copied().unwrap_or(layer as u32 + 1)
+    }
+}
+
+// converts millimetres in the view box to plotter units relative to its corner
+fn to_units(point: &Vec2, view_box: ViewBox, flip_y: bool) -> (i32, i32) {
+    let x = point.x - view_box.x;
+    let y = if flip_y {
+        view_box.bottom() - point.y
+    } else {
+        point.y - view_box.y
+    };
+    ((x * UNITS_PER_MM).round() as i32, (y * UNITS_PER_MM).round() as i32)
+}
+
+fn input_window(clip: ViewBox, view_box: ViewBox, flip_y: bool) -> String {
+    let (ax, ay) = to_units(&Vec2::new(clip.x, clip.y), view_box, flip_y);
+    let (bx, by) = to_units(&Vec2::new(clip.right(), clip.bottom()), view_box, flip_y);
+    format!("IW{},{},{},{};", ax.min(bx), ay.min(by), ax.max(bx), ay.max(by))
+}
+
+/// Converts the paper to HPGL instructions, one per line. Every layer is
+/// drawn with its own `SP` pen.
+pub fn hpgl_instructions(paper: &Paper, options: &HpglOptions) -> Vec<String> {
+    let mut instructions = vec!["IN;".to_string()];
+    if let Some(clip) = options.clip {
+        instructions.push(input_window(clip, paper.view_box, options.flip_y));
+    }
+
+    for (index, layer) in paper.layers().iter().enumerate() {
+        if layer.polylines().is_empty() {
+            continue;
+        }
+        instructions.push(format!("SP{};", options.pen(index)));
+        for polyline in layer.polylines() {
+            let mut points =
+                polyline.points_drawn().map(|p| to_units(p, paper.view_box, options.flip_y));
+            let Some((x, y)) = points.next() else {
+                continue;
+            };
+            instructions.push(format!("PU{x},{y};"));
+            let coordinates: Vec<_> = points.map(|(x, y)| format!("{x},{y}")).collect();
+            // a single point still needs the pen to touch the paper
+            if coordinates.is_empty() {
+                instructions.push(format!("PD{x},{y};"));
+            } else {
+                instructions.push(format!("PD{};", coordinates.join(",")));
+            }
+        }
+    }
+
+    instructions.push("PU;".to_string());
+    instructions.push("SP0;".to_string());
+    instructions
+}
+
+pub fn write_hpgl(paper: &Paper, writer: impl Write, options: &HpglOptions) -> io::Result<()> {
+    let mut writer = BufWriter::new(writer);
+    for instruction in hpgl_instructions(paper, options) {
+        writeln!(writer, "{instruction}")?;
+    }
+    writer.flush()
+}
+
+pub fn save_hpgl(paper: &Paper, filename: &str, options: &HpglOptions) -> io::Result<()> {
+    write_hpgl(paper, File::create(filename)?, options)
+}
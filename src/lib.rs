@@ -1,41 +1,70 @@
 pub mod geometry;
 pub mod geometries {
+    pub mod algebra;
     pub mod blend;
+    pub mod closest_point;
     pub mod gaussian;
     pub mod heightmap;
     pub mod hole;
+    pub mod minimal;
+    pub mod non_orientable;
     pub mod plane;
+    pub mod pseudospherical;
     pub mod pulse;
+    pub mod quadrics;
+    pub mod saddles;
     pub mod sphere;
     pub mod sum;
+    pub mod superquadric;
     pub mod torus;
+    pub mod transformed;
     mod zero;
 }
+pub mod grbl {
+    pub mod driver;
+    pub mod simulator;
+}
 pub mod audio_sync;
+pub mod autodiff;
+pub mod bezier;
 pub mod buffer;
+pub mod calibration;
 pub mod camera;
+pub mod clip;
+pub mod dash;
 pub mod duration_extras;
 pub mod eq;
 pub mod field;
 pub mod fields;
+pub mod finite_difference;
+pub mod gcode;
 pub mod gridlines;
+pub mod hatch;
+pub mod hpgl;
 pub mod integrate;
 pub mod lerp;
 pub mod marching_squares;
+pub mod merge;
 pub mod mesh2;
 pub mod mesh3;
 pub mod mesh3_io;
 pub mod netbm;
+pub mod offset;
+pub mod optimize;
 pub mod paper;
 pub mod polyline;
+pub mod preview;
 pub mod raytracer;
 pub mod resolution;
 pub mod sdf;
 pub mod sdf_transform;
 pub mod simplex;
 pub mod skia_utils;
+pub mod svg_import;
+pub mod text;
 pub mod time_estimator;
 pub mod uv2xy;
+pub mod view_box;
 
 #[cfg(test)]
 mod tests;
@@ -61,8 +61,8 @@ fn continuation(residual: &Vec<Vec<usize>>, vertex: Option<&usize>) -> Option<us
 }
 
 // Find chains in a bag of edges. For example [(0,1), (1,2)] will result in
-// [[0, 1, 2]]
-fn find_chains(edges: &[Edge]) -> Vec<Vec<usize>> {
+// [[0, 1, 2]]. Chains that return to their first vertex are marked closed.
+fn find_chains(edges: &[Edge]) -> Vec<(Vec<usize>, bool)> {
     // 1. Find top edge value and use a vector instead of hash table
     let Some(&top) = edges.iter().map(|(e0, e1)| e0.max(e1)).max() else {
         return Vec::new();
@@ -75,7 +75,7 @@ fn find_chains(edges: &[Edge]) -> Vec<Vec<usize>> {
         residual[*b].push(*a);
     }
 
-    let mut chains: Vec<Vec<usize>> = Vec::new();
+    let mut chains: Vec<(Vec<usize>, bool)> = Vec::new();
     // Keep taking vertices until completely empty
     while let Some(vertex) = next_non_empty(&residual) {
         let mut chain = vec![vertex];
@@ -93,6 +93,15 @@ fn find_chains(edges: &[Edge]) -> Vec<Vec<usize>> {
             }
         }
 
+        // A remaining edge back to the first vertex closes the loop
+        let (first, last) = (chain[0], chain[chain.len() - 1]);
+        if chain.len() > 2 && residual[last].contains(&first) {
+            residual[last].retain(|&v| v != first);
+            residual[first].retain(|&v| v != last);
+            chains.push((chain, true));
+            continue;
+        }
+
         // Search backwards
         while let Some(a) = continuation(&residual, chain.first()) {
             if let Some(&b) = residual[a].iter().filter(|v| !chain.contains(v)).next() {
@@ -105,7 +114,7 @@ fn find_chains(edges: &[Edge]) -> Vec<Vec<usize>> {
                 break;
             }
         }
-        chains.push(chain);
+        chains.push((chain, false));
     }
     chains
 }
@@ -169,6 +178,9 @@ pub fn find_contours(grid: &Field<f32>, level: f32) -> Vec<Polyline2> {
     // find chains and convert vertex ids to cordinates
     find_chains(&lines)
         .iter()
-        .map(|chain| chain.iter().map(|&v| vertices[v]).collect())
+        .map(|(chain, closed)| Polyline2 {
+            points: chain.iter().map(|&v| vertices[v]).collect(),
+            closed: *closed,
+        })
         .collect()
 }
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use nalgebra_glm::Vec2;
+
+use crate::polyline::Polyline2;
+
+/// Number of polylines before and after merging
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MergeReport {
+    pub before: usize,
+    pub after: usize,
+}
+
+// Snaps endpoints that are within the tolerance of each other to one node
+struct Nodes {
+    tolerance: f32,
+    cell: f32,
+    positions: Vec<Vec2>,
+    grid: HashMap<(i64, i64), Vec<usize>>,
+}
+
+impl Nodes {
+    fn new(tolerance: f32) -> Nodes {
+        Nodes {
+            tolerance,
+            cell: tolerance.max(1e-6),
+            positions: Vec::new(),
+            grid: HashMap::new(),
+        }
+    }
+
+    fn key(&self, point: &Vec2) -> (i64, i64) {
+        ((point.x / self.cell).floor() as i64, (point.y / self.cell).floor() as i64)
+    }
+
+    fn find_or_insert(&mut self, point: &Vec2) -> usize {
+        let (x, y) = self.key(point);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let Some(nodes) = self.grid.get(&(x + dx, y + dy)) else {
+                    continue;
+                };
+                for &node in nodes {
+                    if (self.positions[node] - point).norm() <= self.tolerance {
+                        return node;
+                    }
+                }
+            }
+        }
+        let node = self.positions.len();
+        self.positions.push(*point);
+        self.grid.entry((x, y)).or_default().push(node);
+        node
+    }
+}
+
+/// Joins polylines whose endpoints are within `tolerance`, reversing them
+/// where needed. Endpoints become nodes of a graph and every polyline an
+/// edge. Chains are walked starting at odd nodes, so each chain runs as far
+/// as the graph allows before the pen has to be lifted. Chains that return
+/// to where they started become closed polylines.
+pub fn merge(polylines: &[Polyline2], tolerance: f32) -> Vec<Polyline2> {
+    let mut nodes = Nodes::new(tolerance);
+    let mut merged = Vec::new();
+    // (polyline, start node, end node)
+    let mut edges = Vec::new();
+    for polyline in polylines {
+        match (polyline.points.first(), polyline.points.last()) {
+            (Some(first), Some(last)) if polyline.points.len() > 1 && !polyline.closed => {
+                let start = nodes.find_or_insert(first);
+                let end = nodes.find_or_insert(last);
+                edges.push((polyline, start, end));
+            }
+            // dots and loops have nothing to connect
+            _ => merged.push(polyline.clone()),
+        }
+    }
+
+    let mut adjacent = vec![Vec::new(); nodes.positions.len()];
+    // a polyline whose ends meet is listed twice, as it adds two to the degree
+    for (edge, &(_, start, end)) in edges.iter().enumerate() {
+        adjacent[start].push(edge);
+        adjacent[end].push(edge);
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut next = vec![0; nodes.positions.len()];
+    let mut unused_edge = |node: usize, used: &[bool]| {
+        while next[node] < adjacent[node].len() {
+            let edge = adjacent[node][next[node]];
+            if !used[edge] {
+                return Some(edge);
+            }
+            next[node] += 1;
+        }
+        None
+    };
+
+    let odd: Vec<_> = (0..adjacent.len()).filter(|&node| adjacent[node].len() % 2 == 1).collect();
+    // open chains first, then whatever is left are loops
+    for start in odd.into_iter().chain(0..adjacent.len()) {
+        while let Some(first) = unused_edge(start, &used) {
+            let mut chain = Polyline2::new();
+            let mut node = start;
+            let mut edge = first;
+            loop {
+                used[edge] = true;
+                let (polyline, a, b) = edges[edge];
+                let mut points = polyline.points.clone();
+                if a != node {
+                    points.reverse();
+                }
+                // skip the shared point unless the ends only nearly touch
+                let skip = chain.points.last() == points.first();
+                chain.points.extend(points.into_iter().skip(skip as usize));
+                node = if a == node { b } else { a };
+                match unused_edge(node, &used) {
+                    Some(e) => edge = e,
+                    None => break,
+                }
+            }
+            // back at the start, the chain is a loop
+            if node == start {
+                if chain.points.len() > 2 && chain.points.first() == chain.points.last() {
+                    chain.points.pop();
+                }
+                chain.closed = true;
+            }
+            merged.push(chain);
+        }
+    }
+    merged
+}
@@ -0,0 +1,348 @@
+use std::collections::HashMap;
+
+use nalgebra_glm::{rotate_vec2, Vec2};
+
+use crate::polyline::Polyline2;
+
+// largest distance in mm between a round join and its polyline
+const ARC_TOLERANCE: f32 = 0.01;
+
+/// How the offset outline goes around corners that point away from the shape
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Join {
+    Round,
+    // corners reaching further out than `limit` times the distance are beveled
+    Miter(f32),
+    Bevel,
+}
+
+fn cross(a: &Vec2, b: &Vec2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+// positive for counter-clockwise points in y-up coordinates
+fn signed_area(points: &[Vec2]) -> f32 {
+    let n = points.len();
+    0.5 * (0..n).map(|i| cross(&points[i], &points[(i + 1) % n])).sum::<f32>()
+}
+
+// the points of an outline without repeats, the closing point included
+fn cleaned(outline: &Polyline2) -> Vec<Vec2> {
+    let mut points: Vec<Vec2> = Vec::new();
+    for point in &outline.points {
+        if points.last() != Some(point) {
+            points.push(*point);
+        }
+    }
+    while points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    points
+}
+
+// Moves every edge of a positively oriented loop outwards by `distance` and
+// fills the corners with joins. Concave corners go back through the vertex,
+// which leaves loops that the winding filter removes.
+fn raw_offset(points: &[Vec2], distance: f32, join: Join) -> Vec<Vec2> {
+    let n = points.len();
+    let normal = |i: usize| {
+        let d = points[(i + 1) % n] - points[i];
+        Vec2::new(d.y, -d.x).normalize()
+    };
+    let mut raw = Vec::new();
+    for (i, &p) in points.iter().enumerate() {
+        let (n1, n2) = (normal((i + n - 1) % n), normal(i));
+        let (a, b) = (p + distance * n1, p + distance * n2);
+        let (sin, cos) = (cross(&n1, &n2), n1.dot(&n2));
+        if sin * distance < -1e-6 * distance.abs() {
+            raw.extend([a, p, b]);
+            continue;
+        }
+        if cos > 1.0 - 1e-6 {
+            raw.push(a);
+            continue;
+        }
+        match join {
+            Join::Miter(limit) if (2.0 / (1.0 + cos)).sqrt() <= limit => {
+                raw.push(p + distance * (n1 + n2) / (1.0 + cos));
+            }
+            Join::Miter(_) | Join::Bevel => raw.extend([a, b]),
+            Join::Round => {
+                let angle = sin.atan2(cos);
+                let step = match ARC_TOLERANCE < distance.abs() {
+                    true => 2.0 * (1.0 - ARC_TOLERANCE / distance.abs()).acos(),
+                    false => std::f32::consts::FRAC_PI_2,
+                };
+                let steps = (angle.abs() / step).ceil().max(1.0) as usize;
+                raw.extend(
+                    (0..=steps)
+                        .map(|k| p + distance * rotate_vec2(&n1, angle * k as f32 / steps as f32)),
+                );
+            }
+        }
+    }
+    raw
+}
+
+// how often the edges wind around the point, counter-clockwise positive
+fn winding(point: &Vec2, segments: &[(Vec2, Vec2)]) -> i32 {
+    let mut winding = 0;
+    for (a, b) in segments {
+        let side = cross(&(b - a), &(point - a));
+        if a.y <= point.y && b.y > point.y && side > 0.0 {
+            winding += 1;
+        } else if b.y <= point.y && a.y > point.y && side < 0.0 {
+            winding -= 1;
+        }
+    }
+    winding
+}
+
+// Outline of the area whose winding number is `filled`. Segments are split
+// where they cross, pieces between the area and the outside are turned to
+// have the area on their left and joined into loops, so outer loops turn
+// counter-clockwise and holes clockwise.
+fn filled_area(loops: &[Vec<Vec2>], filled: impl Fn(i32) -> bool) -> Vec<Polyline2> {
+    let segments: Vec<(Vec2, Vec2)> = loops
+        .iter()
+        .flat_map(|points| {
+            let n = points.len();
+            (0..n).map(move |i| (points[i], points[(i + 1) % n]))
+        })
+        .filter(|(a, b)| a != b)
+        .collect();
+    let Some((min, max)) = segments
+        .iter()
+        .map(|(a, _)| (*a, *a))
+        .reduce(|(min, max), (p, _)| (min.inf(&p), max.sup(&p)))
+    else {
+        return Vec::new();
+    };
+    let epsilon = 1e-5 * (max - min).max().max(1e-3);
+
+    // split points of every segment, the same point is used on both sides
+    let mut splits: Vec<Vec<(f32, Vec2)>> = vec![Vec::new(); segments.len()];
+    for i in 0..segments.len() {
+        let (a1, b1) = segments[i];
+        let d1 = b1 - a1;
+        for j in i + 1..segments.len() {
+            let (a2, b2) = segments[j];
+            if a1.x.max(b1.x) < a2.x.min(b2.x)
+                || a2.x.max(b2.x) < a1.x.min(b1.x)
+                || a1.y.max(b1.y) < a2.y.min(b2.y)
+                || a2.y.max(b2.y) < a1.y.min(b1.y)
+            {
+                continue;
+            }
+            let d2 = b2 - a2;
+            let denominator = cross(&d1, &d2);
+            if denominator.abs() < f32::EPSILON * d1.norm() * d2.norm() {
+                continue;
+            }
+            let t = cross(&(a2 - a1), &d2) / denominator;
+            let s = cross(&(a2 - a1), &d1) / denominator;
+            if !(0.0..=1.0).contains(&t) || !(0.0..=1.0).contains(&s) {
+                continue;
+            }
+            let end = 1e-6;
+            let point = match () {
+                _ if t <= end => a1,
+                _ if t >= 1.0 - end => b1,
+                _ if s <= end => a2,
+                _ if s >= 1.0 - end => b2,
+                _ => a1 + t * d1,
+            };
+            if t > end && t < 1.0 - end {
+                splits[i].push((t, point));
+            }
+            if s > end && s < 1.0 - end {
+                splits[j].push((s, point));
+            }
+        }
+    }
+
+    let mut kept = Vec::new();
+    for (&(a, b), cuts) in segments.iter().zip(&mut splits) {
+        cuts.sort_by(|x, y| x.0.total_cmp(&y.0));
+        let mut points = vec![a];
+        points.extend(cuts.iter().map(|&(_, point)| point));
+        points.push(b);
+        points.dedup();
+        for piece in points.windows(2) {
+            let (p, q) = (piece[0], piece[1]);
+            let d = (q - p).normalize();
+            let left = epsilon * Vec2::new(-d.y, d.x);
+            let middle = 0.5 * (p + q);
+            let on_left = filled(winding(&(middle + left), &segments));
+            let on_right = filled(winding(&(middle - left), &segments));
+            match (on_left, on_right) {
+                (true, false) => kept.push((p, q)),
+                (false, true) => kept.push((q, p)),
+                _ => {}
+            }
+        }
+    }
+
+    // ends that only differ by rounding become one node
+    let mut nodes: Vec<Vec2> = Vec::new();
+    let mut grid: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    let mut node = |p: &Vec2| {
+        let (x, y) = ((p.x / epsilon).floor() as i64, (p.y / epsilon).floor() as i64);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let cell = grid.get(&(x + dx, y + dy)).into_iter().flatten();
+                if let Some(&n) = cell.into_iter().find(|&&n| (nodes[n] - p).norm() <= epsilon) {
+                    return n;
+                }
+            }
+        }
+        nodes.push(*p);
+        grid.entry((x, y)).or_default().push(nodes.len() - 1);
+        nodes.len() - 1
+    };
+    let edges: Vec<(usize, usize)> =
+        kept.iter().map(|(p, q)| (node(p), node(q))).filter(|(a, b)| a != b).collect();
+
+    let mut starting: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (index, &(a, _)) in edges.iter().enumerate() {
+        starting.entry(a).or_default().push(index);
+    }
+    let mut used = vec![false; edges.len()];
+    let mut result = Vec::new();
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+        let mut points = Vec::new();
+        let mut edge = first;
+        let closed = loop {
+            used[edge] = true;
+            let (a, b) = edges[edge];
+            points.push(nodes[a]);
+            if b == edges[first].0 {
+                break true;
+            }
+            // the sharpest left turn keeps to the area, so parts that only
+            // touch at a point become loops of their own
+            let incoming = nodes[b] - nodes[a];
+            let turn = |e: usize| {
+                let outgoing = nodes[edges[e].1] - nodes[b];
+                cross(&incoming, &outgoing).atan2(incoming.dot(&outgoing))
+            };
+            let next = starting.get(&b).and_then(|next| {
+                next.iter()
+                    .filter(|&&e| !used[e])
+                    .max_by(|&&x, &&y| turn(x).total_cmp(&turn(y)))
+            });
+            match next {
+                Some(&e) => edge = e,
+                None => break false,
+            }
+        };
+        // drop points in the middle of straight runs
+        let n = points.len();
+        let straight: Vec<bool> = (0..n)
+            .map(|i| {
+                let (a, b) = (points[(i + n - 1) % n] - points[i], points[(i + 1) % n] - points[i]);
+                cross(&a, &b).abs() <= epsilon * a.norm().max(b.norm()) && a.dot(&b) < 0.0
+            })
+            .collect();
+        let points: Vec<Vec2> =
+            points.iter().zip(&straight).filter(|(_, &s)| !s).map(|(p, _)| *p).collect();
+        if closed && points.len() > 2 && signed_area(&points).abs() > epsilon * epsilon {
+            result.push(Polyline2 { points, closed: true });
+        }
+    }
+    result
+}
+
+/// Grows the area enclosed by the outlines by `distance`, or shrinks it for
+/// negative distances. The area is taken with the even-odd rule, so outlines
+/// nested in an odd number of others are holes and the lobes of a crossing
+/// outline are all filled. Self-intersections of the moved outlines are
+/// resolved too, so parts can merge, split or vanish. Holes come out with the
+/// opposite winding of outer outlines.
+pub fn offset(outlines: &[Polyline2], distance: f32, join: Join) -> Vec<Polyline2> {
+    let cleaned: Vec<Vec<Vec2>> = outlines.iter().map(cleaned).filter(|p| p.len() > 2).collect();
+    // simple loops first, every edge with the area on its left
+    let loops: Vec<Vec<Vec2>> = filled_area(&cleaned, |winding| winding % 2 != 0)
+        .iter()
+        .map(|outline| raw_offset(&outline.points, distance, join))
+        .collect();
+    filled_area(&loops, |winding| winding > 0)
+}
+
+/// Outlines shrunk by `spacing / 2`, `3 spacing / 2` and so on until nothing
+/// is left, one entry per step. Every step is offset from the original
+/// outlines so errors do not add up.
+pub fn insets(outlines: &[Polyline2], spacing: f32, join: Join) -> Vec<Vec<Polyline2>> {
+    let mut levels = Vec::new();
+    if spacing <= 0.0 {
+        return levels;
+    }
+    loop {
+        let distance = -(levels.len() as f32 + 0.5) * spacing;
+        let level = offset(outlines, distance, join);
+        if level.is_empty() {
+            return levels;
+        }
+        levels.push(level);
+    }
+}
+
+// closest point on a closed polyline, and the edge it is on
+fn closest_on(ring: &Polyline2, point: &Vec2) -> (usize, Vec2) {
+    let n = ring.points.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (ring.points[i], ring.points[(i + 1) % n]);
+            let d = b - a;
+            let t = ((point - a).dot(&d) / d.norm_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+            (i, a + t * d)
+        })
+        .min_by(|x, y| (x.1 - point).norm().total_cmp(&(y.1 - point).norm()))
+        .unwrap()
+}
+
+/// Fills the outlines with concentric rings `spacing` apart. Each ring is
+/// entered from the ring around it with a short step, so a shape that does
+/// not split plots as one spiral-like stroke.
+pub fn contour_fill(outlines: &[Polyline2], spacing: f32, join: Join) -> Vec<Polyline2> {
+    let mut finished = Vec::new();
+    // strokes that ended on the previous level and may continue inwards
+    let mut active: Vec<Polyline2> = Vec::new();
+    for level in insets(outlines, spacing, join) {
+        let mut extended = Vec::new();
+        for ring in level {
+            let nearest = active
+                .iter()
+                .enumerate()
+                .map(|(i, stroke)| (i, closest_on(&ring, stroke.points.last().unwrap())))
+                .filter(|(i, (_, point))| {
+                    (point - active[*i].points.last().unwrap()).norm() < 1.5 * spacing
+                })
+                .min_by(|x, y| {
+                    let distance = |(i, (_, point)): &(usize, (usize, Vec2))| {
+                        (point - active[*i].points.last().unwrap()).norm()
+                    };
+                    distance(x).total_cmp(&distance(y))
+                });
+
+            let (mut stroke, (edge, start)) = match nearest {
+                Some((i, entry)) => (active.swap_remove(i), entry),
+                None => (Polyline2::new(), (ring.points.len() - 1, ring.points[0])),
+            };
+            // around the ring from the entry point back to it
+            stroke.add(start);
+            let n = ring.points.len();
+            stroke.points.extend((1..=n).map(|k| ring.points[(edge + k) % n]));
+            stroke.add(start);
+            stroke.points.dedup();
+            extended.push(stroke);
+        }
+        finished.append(&mut active);
+        active = extended;
+    }
+    finished.append(&mut active);
+    finished
+}
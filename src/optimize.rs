@@ -0,0 +1,323 @@
+use std::time::{Duration, Instant};
+
+use nalgebra_glm::Vec2;
+
+use crate::polyline::Polyline2;
+
+pub struct OptimizeOptions {
+    // allow drawing a polyline backwards when its end is closer
+    pub reverse: bool,
+    // time spent on 2-opt and Or-opt refinement after the greedy pass
+    pub time_budget: Duration,
+    // how many positions ahead the refinement looks for improving moves
+    pub window: usize,
+}
+
+impl Default for OptimizeOptions {
+    fn default() -> Self {
+        Self {
+            reverse: true,
+            time_budget: Duration::from_secs(2),
+            window: 64,
+        }
+    }
+}
+
+/// Pen-up travel in mm before and after optimizing, as measured by
+/// `Paper::length`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OptimizeReport {
+    pub travel_before: f32,
+    pub travel_after: f32,
+}
+
+impl OptimizeReport {
+    pub fn saved(&self) -> f32 {
+        self.travel_before - self.travel_after
+    }
+}
+
+// how a polyline is drawn, the grid is searched by the start point
+#[derive(Clone, Copy)]
+struct Visit {
+    index: usize,
+    // first vertex drawn, closed polylines may start anywhere
+    vertex: usize,
+    reversed: bool,
+    start: Vec2,
+    end: Vec2,
+}
+
+// Uniform grid over the polyline endpoints for nearest neighbour queries
+struct Grid {
+    origin: Vec2,
+    size: f32,
+    columns: usize,
+    rows: usize,
+    cells: Vec<Vec<Visit>>,
+    // entries left in the cells
+    len: usize,
+}
+
+impl Grid {
+    fn new(entries: &[Visit]) -> Grid {
+        let mut min = Vec2::repeat(f32::INFINITY);
+        let mut max = Vec2::repeat(f32::NEG_INFINITY);
+        for visit in entries {
+            min = min.inf(&visit.start);
+            max = max.sup(&visit.start);
+        }
+        let n = entries.len().max(1) as f32;
+        let (w, h) = ((max.x - min.x).max(0.0), (max.y - min.y).max(0.0));
+        // about one entry per cell
+        let size = (w * h / n).sqrt().max(w.max(h) / n).max(1e-3);
+        let columns = (w / size) as usize + 1;
+        let rows = (h / size) as usize + 1;
+        let mut grid = Grid {
+            origin: min,
+            size,
+            columns,
+            rows,
+            cells: vec![Vec::new(); columns * rows],
+            len: entries.len(),
+        };
+        for &visit in entries {
+            let cell = grid.cell(&visit.start);
+            grid.cells[cell].push(visit);
+        }
+        grid
+    }
+
+    fn coordinates(&self, point: &Vec2) -> (usize, usize) {
+        let x = ((point.x - self.origin.x) / self.size).max(0.0) as usize;
+        let y = ((point.y - self.origin.y) / self.size).max(0.0) as usize;
+        (x.min(self.columns - 1), y.min(self.rows - 1))
+    }
+
+    fn cell(&self, point: &Vec2) -> usize {
+        let (x, y) = self.coordinates(point);
+        y * self.columns + x
+    }
+
+    fn remove(&mut self, point: &Vec2, index: usize) {
+        let cell = self.cell(point);
+        let before = self.cells[cell].len();
+        self.cells[cell].retain(|visit| visit.index != index);
+        self.len -= before - self.cells[cell].len();
+    }
+
+    fn search(&self, x: isize, y: isize, point: &Vec2, best: &mut Option<(f32, Visit)>) -> usize {
+        if x < 0 || y < 0 || x >= self.columns as isize || y >= self.rows as isize {
+            return 0;
+        }
+        let cell = &self.cells[y as usize * self.columns + x as usize];
+        for visit in cell {
+            let distance = (visit.start - point).norm();
+            if best.is_none_or(|(d, _)| distance < d) {
+                *best = Some((distance, *visit));
+            }
+        }
+        cell.len()
+    }
+
+    // searches the borders of growing rings of cells around the point until
+    // nothing closer can exist or every entry has been seen
+    fn nearest(&self, point: &Vec2) -> Option<Visit> {
+        let (cx, cy) = self.coordinates(point);
+        let (cx, cy) = (cx as isize, cy as isize);
+        let mut best: Option<(f32, Visit)> = None;
+        let mut seen = self.search(cx, cy, point, &mut best);
+        for r in 1..=self.columns.max(self.rows) as isize {
+            if seen == self.len || best.is_some_and(|(d, _)| d <= (r - 1) as f32 * self.size) {
+                break;
+            }
+            for x in cx - r..=cx + r {
+                seen += self.search(x, cy - r, point, &mut best);
+                seen += self.search(x, cy + r, point, &mut best);
+            }
+            for y in cy - r + 1..cy + r {
+                seen += self.search(cx - r, y, point, &mut best);
+                seen += self.search(cx + r, y, point, &mut best);
+            }
+        }
+        best.map(|(_, visit)| visit)
+    }
+}
+
+struct Tour {
+    home: Vec2,
+    order: Vec<Visit>,
+}
+
+impl Tour {
+    fn start(&self, k: usize) -> Vec2 {
+        self.order[k].start
+    }
+
+    fn end(&self, k: usize) -> Vec2 {
+        self.order[k].end
+    }
+
+    // pen position before drawing position k
+    fn before(&self, k: usize) -> Vec2 {
+        if k == 0 {
+            self.home
+        } else {
+            self.end(k - 1)
+        }
+    }
+
+    // travel from a point to position k, nothing after the last polyline
+    fn link(&self, point: Vec2, k: usize) -> f32 {
+        if k < self.order.len() {
+            (self.start(k) - point).norm()
+        } else {
+            0.0
+        }
+    }
+
+    // reverses a stretch of the tour including the direction of each polyline
+    fn two_opt(&mut self, window: usize, deadline: Instant) -> bool {
+        let n = self.order.len();
+        let mut improved = false;
+        for i in 0..n {
+            if Instant::now() > deadline {
+                break;
+            }
+            for j in i + 1..n.min(i + window) {
+                let before = self.before(i);
+                let old = (self.start(i) - before).norm() + self.link(self.end(j), j + 1);
+                let new = (self.end(j) - before).norm() + self.link(self.start(i), j + 1);
+                if new < old - 1e-4 {
+                    self.order[i..=j].reverse();
+                    for visit in &mut self.order[i..=j] {
+                        visit.reversed = !visit.reversed;
+                        std::mem::swap(&mut visit.start, &mut visit.end);
+                    }
+                    improved = true;
+                }
+            }
+        }
+        improved
+    }
+
+    // moves short chains of polylines to a better place nearby
+    fn or_opt(&mut self, window: usize, deadline: Instant) -> bool {
+        let n = self.order.len();
+        let mut improved = false;
+        for length in 1..=3 {
+            for i in 0..n.saturating_sub(length - 1) {
+                if Instant::now() > deadline {
+                    return improved;
+                }
+                let last = i + length - 1;
+                let (first, end) = (self.start(i), self.end(last));
+                let before = self.before(i);
+                let removed = (first - before).norm() + self.link(end, last + 1)
+                    - self.link(before, last + 1);
+                let lower = i.saturating_sub(window) as isize - 1;
+                let upper = (last + window).min(n - 1) as isize;
+                // insert between position p and p + 1, p = -1 is right after home
+                for p in lower..=upper {
+                    if p >= i as isize - 1 && p <= last as isize {
+                        continue;
+                    }
+                    let after = if p < 0 {
+                        self.home
+                    } else {
+                        self.end(p as usize)
+                    };
+                    let next = (p + 1) as usize;
+                    let added =
+                        (first - after).norm() + self.link(end, next) - self.link(after, next);
+                    if added < removed - 1e-4 {
+                        let chain: Vec<_> = self.order.drain(i..=last).collect();
+                        let at = if next < i { next } else { next - length };
+                        self.order.splice(at..at, chain);
+                        improved = true;
+                        break;
+                    }
+                }
+            }
+        }
+        improved
+    }
+}
+
+/// Orders polylines to reduce pen-up travel when starting at `home`. A greedy
+/// nearest neighbour pass over a spatial grid is refined with 2-opt and
+/// Or-opt moves until no move improves or the time budget is used up. Closed
+/// polylines may start at any of their vertices.
+pub fn optimize(polylines: &[Polyline2], home: Vec2, options: &OptimizeOptions) -> Vec<Polyline2> {
+    let deadline = Instant::now() + options.time_budget;
+    // the points a polyline can be entered at
+    let entry_points = |polyline: &Polyline2| -> Vec<Vec2> {
+        match (polyline.closed, polyline.points.first(), polyline.points.last()) {
+            (true, _, _) => polyline.points.clone(),
+            (false, Some(&first), Some(&last)) => vec![first, last],
+            _ => Vec::new(),
+        }
+    };
+
+    let mut entries = Vec::new();
+    for (index, polyline) in polylines.iter().enumerate() {
+        let points = entry_points(polyline);
+        if polyline.closed {
+            // the direction of a loop does not change the travel
+            for (vertex, &point) in points.iter().enumerate() {
+                entries.push(Visit { index, vertex, reversed: false, start: point, end: point });
+            }
+        } else if let [first, last] = points[..] {
+            entries.push(Visit { index, vertex: 0, reversed: false, start: first, end: last });
+            if options.reverse && polyline.points.len() > 1 {
+                let vertex = polyline.points.len() - 1;
+                entries.push(Visit { index, vertex, reversed: true, start: last, end: first });
+            }
+        }
+    }
+    let mut grid = Grid::new(&entries);
+
+    let mut order = Vec::with_capacity(polylines.len());
+    let mut pen = home;
+    while let Some(visit) = grid.nearest(&pen) {
+        for point in entry_points(&polylines[visit.index]) {
+            grid.remove(&point, visit.index);
+        }
+        order.push(visit);
+        pen = visit.end;
+    }
+
+    let mut tour = Tour { home, order };
+    loop {
+        let mut improved = false;
+        if options.reverse {
+            improved |= tour.two_opt(options.window, deadline);
+        }
+        improved |= tour.or_opt(options.window, deadline);
+        if !improved || Instant::now() > deadline {
+            break;
+        }
+    }
+
+    let mut optimized: Vec<_> = tour
+        .order
+        .iter()
+        .map(|visit| {
+            let mut polyline = polylines[visit.index].clone();
+            if polyline.closed {
+                polyline.points.rotate_left(visit.vertex);
+                if visit.reversed {
+                    // keep the start vertex in front
+                    polyline.points.reverse();
+                    polyline.points.rotate_right(1);
+                }
+            } else if visit.reversed {
+                polyline.points.reverse();
+            }
+            polyline
+        })
+        .collect();
+    // empty polylines draw nothing, keep them at the end
+    optimized.extend(polylines.iter().filter(|p| p.points.is_empty()).cloned());
+    optimized
+}
@@ -1,38 +1,260 @@
-use std::collections::HashSet;
-use std::io;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 use std::ops::Sub;
+use std::path::Path;
 
-use nalgebra_glm::Vec2;
-use svg::node::element::Group;
+use nalgebra_glm::{rotation2d, scaling2d, translation2d, Mat3, Vec2, Vec3};
+use svg::node::element::{Group, Polygon};
 use svg::{Document, Node};
 
+use crate::bezier::fit;
+use crate::clip::clip_to_view_box;
+use crate::dash::dash;
+use crate::hatch::{hatch, HatchOptions};
+use crate::merge::{merge, MergeReport};
+use crate::optimize::{optimize, OptimizeOptions, OptimizeReport};
 use crate::polyline::Polyline2;
+use crate::view_box::ViewBox;
 
-pub type ViewBox = (i32, i32, i32, i32);
-pub const A4_PORTRAIT: ViewBox = (0, 0, 210, 297);
-pub const A4_LANDSCAPE: ViewBox = (0, 0, 297, 210);
+/// Preview color of a layer, written as `#rrggbb` in the SVG output
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
 
-pub fn viewbox_aspect(view_box: ViewBox) -> f32 {
-    view_box.2 as f32 / view_box.3 as f32
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const RED: Rgb = Rgb(255, 0, 0);
+    pub const GREEN: Rgb = Rgb(0, 128, 0);
+    pub const BLUE: Rgb = Rgb(0, 0, 255);
+
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// One pen pass. Each layer has its own pen width and preview color and is
+/// ordered and measured on its own.
+pub struct Layer {
+    pub name: String,
+    pub pen: f32,
+    pub color: Rgb,
+    polylines: Vec<Polyline2>,
+}
+
+impl Layer {
+    pub fn new(name: &str, pen: f32, color: Rgb) -> Layer {
+        Layer { name: name.to_string(), pen, color, polylines: Vec::new() }
+    }
+
+    pub fn add(&mut self, polyline: Polyline2) {
+        self.polylines.push(polyline);
+    }
+
+    pub fn polylines(&self) -> &[Polyline2] {
+        &self.polylines
+    }
+
+    // computes drawing distance and moving distance when starting at home.
+    pub fn length(&self, home: Vec2) -> (f32, f32) {
+        let mut drawing = 0.0;
+        let mut moving = 0.0;
+        let mut pen = home;
+        for polyline in &self.polylines {
+            // distance from pen to first point
+            moving += pen.sub(polyline.points.first().unwrap_or(&pen)).norm();
+            drawing += polyline.length();
+            pen = *polyline.end().unwrap_or(&pen);
+        }
+        (drawing, moving)
+    }
+
+    // removes everything outside the area, splitting lines at its border
+    pub fn clip(&mut self, area: ViewBox) {
+        self.polylines = self
+            .polylines
+            .iter()
+            .flat_map(|polyline| clip_to_view_box(polyline, area))
+            .collect();
+    }
+
+    // bounding box of all points, None if the layer is empty
+    pub fn bounds(&self) -> Option<ViewBox> {
+        ViewBox::bounding(self.polylines.iter().flat_map(|polyline| &polyline.points))
+    }
+
+    // applies a 2D affine transformation to all points
+    pub fn transform(&mut self, matrix: &Mat3) {
+        for polyline in &mut self.polylines {
+            for point in &mut polyline.points {
+                *point = (matrix * Vec3::new(point.x, point.y, 1.0)).xy();
+            }
+        }
+    }
+
+    // joins poly-lines whose endpoints are within the tolerance
+    pub fn merge(&mut self, tolerance: f32) {
+        self.polylines = merge(&self.polylines, tolerance);
+    }
+
+    // re-orders and reverses poly-lines for faster plotting
+    pub fn optimize(&mut self, home: Vec2, options: &OptimizeOptions) {
+        self.polylines = optimize(&self.polylines, home, options);
+    }
+}
+
+// Mirror::Horizontal swaps left and right, Mirror::Vertical top and bottom
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mirror {
+    Horizontal,
+    Vertical,
+}
+
+// applies the transformation with `center` as the origin
+fn around(matrix: Mat3, center: Vec2) -> Mat3 {
+    translation2d(&center) * matrix * translation2d(&-center)
 }
 
-pub fn pad(view_box: ViewBox, pad: i32) -> ViewBox {
-    let (x, y, w, h) = view_box;
-    (x + pad, y + pad, w - 2 * pad, h - 2 * pad)
+/// How polylines are written to SVG
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SvgFormat {
+    // every point in a `<polyline>` or `<polygon>` at full precision
+    #[default]
+    Polylines,
+    // cubic Béziers fitted within `tolerance` mm, written as `<path>` data
+    // with relative commands and coordinates rounded to `decimals`
+    Paths {
+        tolerance: f32,
+        decimals: usize,
+    },
 }
 
-pub fn contains(view_box: &ViewBox, point: &Vec2) -> bool {
-    let (x, y, w, h) = view_box;
-    point.x > *x as f32
-        && point.y > *y as f32
-        && point.x < (x + w) as f32
-        && point.y < (y + h) as f32
+// Path data in whole units of 10^-decimals. Relative steps are differences
+// of rounded absolute positions, so rounding errors do not add up.
+struct PathData {
+    data: String,
+    scale: f32,
+    decimals: usize,
+    command: char,
+    current: (i64, i64),
+}
+
+impl PathData {
+    fn new(decimals: usize) -> PathData {
+        PathData {
+            data: String::new(),
+            scale: 10f32.powi(decimals as i32),
+            decimals,
+            command: ' ',
+            current: (0, 0),
+        }
+    }
+
+    fn units(&self, point: &Vec2) -> (i64, i64) {
+        ((point.x * self.scale).round() as i64, (point.y * self.scale).round() as i64)
+    }
+
+    fn number(&mut self, value: i64) {
+        let unit = 10i64.pow(self.decimals as u32);
+        let (whole, fraction) = (value.abs() / unit, value.abs() % unit);
+        let mut text = if value < 0 {
+            "-".to_string()
+        } else {
+            String::new()
+        };
+        let fraction = format!("{fraction:0width$}", width = self.decimals);
+        let fraction = fraction.trim_end_matches('0');
+        match (whole, fraction.is_empty()) {
+            (_, true) => text += &whole.to_string(),
+            // ".5" instead of "0.5"
+            (0, false) => text += &format!(".{fraction}"),
+            (_, false) => text += &format!("{whole}.{fraction}"),
+        }
+        let separate = !self.data.ends_with(self.command) && !text.starts_with('-');
+        if separate {
+            self.data.push(' ');
+        }
+        self.data += &text;
+    }
+
+    // a relative command, repeated commands are written once
+    fn relative(&mut self, command: char, points: &[Vec2]) {
+        let start = self.current;
+        if command != self.command || command == 'm' {
+            self.data.push(command);
+            self.command = command;
+        }
+        for point in points {
+            let (x, y) = self.units(point);
+            self.number(x - start.0);
+            self.number(y - start.1);
+            self.current = (x, y);
+        }
+    }
+
+    fn line(&mut self, point: &Vec2) {
+        if self.units(point) != self.current {
+            self.relative('l', std::slice::from_ref(point));
+        }
+    }
+
+    fn close(&mut self, start: (i64, i64)) {
+        self.data.push('z');
+        self.command = 'z';
+        self.current = start;
+    }
+}
+
+fn as_path(polyline: &Polyline2, tolerance: f32, decimals: usize) -> String {
+    let mut path = PathData::new(decimals);
+    let Some(first) = polyline.points.first() else {
+        return path.data;
+    };
+    path.relative('m', std::slice::from_ref(first));
+    let start = path.current;
+    if polyline.points.len() == 1 {
+        path.relative('l', &[*first]);
+        return path.data;
+    }
+    let points: Vec<Vec2> = polyline.points_drawn().copied().collect();
+    let curves = fit(&points, tolerance);
+    for (index, curve) in curves.iter().enumerate() {
+        // the closing line is drawn by `z`
+        let closing = polyline.closed && index == curves.len() - 1;
+        if curve.is_straight(0.1 * tolerance) {
+            if !closing {
+                path.line(&curve.p3);
+            }
+        } else {
+            path.relative('c', &[curve.p1, curve.p2, curve.p3]);
+        }
+    }
+    if polyline.closed {
+        path.close(start);
+    }
+    path.data
+}
+
+// Zero length lines with round caps. Polylines have no rounding of their
+// own, so dots then get a micrometre.
+fn dots_path(dots: &[Vec2], format: SvgFormat) -> svg::node::element::Path {
+    let decimals = match format {
+        SvgFormat::Polylines => 3,
+        SvgFormat::Paths { decimals, .. } => decimals,
+    };
+    let mut path = PathData::new(decimals);
+    for dot in dots {
+        path.relative('m', std::slice::from_ref(dot));
+        path.relative('l', std::slice::from_ref(dot));
+    }
+    svg::node::element::Path::new()
+        .set("stroke-linecap", "round")
+        .set("d", path.data)
 }
 
+/// A sheet of paper holding one or more layers. Layers are plotted in the
+/// order they were added.
 pub struct Paper {
     pub view_box: ViewBox,
-    pub pen: f32,
-    polylines: Vec<Polyline2>,
+    layers: Vec<Layer>,
 }
 
 fn as_node(polyline: &Polyline2) -> String {
@@ -47,74 +269,242 @@ fn as_node(polyline: &Polyline2) -> String {
 
 impl Paper {
     pub fn new(view_box: ViewBox, pen: f32) -> Paper {
-        Paper { view_box, pen, polylines: Vec::new() }
+        Paper {
+            view_box,
+            layers: vec![Layer::new("Layer 1", pen, Rgb::BLACK)],
+        }
+    }
+
+    // pen width of the default layer
+    pub fn pen(&self) -> f32 {
+        self.layers[0].pen
     }
 
+    // adds a polyline to the last layer
     pub fn add(&mut self, polyline: Polyline2) {
-        self.polylines.push(polyline);
+        self.layers.last_mut().unwrap().add(polyline);
     }
 
-    fn distance_to(&self, point: Vec2, index: usize) -> f32 {
-        if let Some(start) = self.polylines[index].points.first() {
-            point.sub(start).norm()
-        } else {
-            f32::INFINITY
+    // appends a new layer and returns its index. Following calls to `add`
+    // will go to this layer.
+    pub fn add_layer(&mut self, name: &str, pen: f32, color: Rgb) -> usize {
+        self.layers.push(Layer::new(name, pen, color));
+        self.layers.len() - 1
+    }
+
+    // a single point, plotted by lowering the pen in place
+    pub fn dot(&mut self, point: Vec2) {
+        self.add(Polyline2 { points: vec![point], closed: false });
+    }
+
+    pub fn dots(&mut self, points: impl IntoIterator<Item = Vec2>) {
+        for point in points {
+            self.dot(point);
         }
     }
 
+    // draws the polyline dashed on the last layer, see `dash::dash`
+    pub fn dash(&mut self, polyline: &Polyline2, pattern: &[f32], phase: f32) {
+        self.extend(dash(polyline, pattern, phase));
+    }
+
+    // fills the outlines with hatch lines on the last layer
+    pub fn hatch(&mut self, outlines: &[Polyline2], options: &HatchOptions) {
+        self.extend(hatch(outlines, options));
+    }
+
+    // solid fill with lines one pen width of the last layer apart
+    pub fn fill(&mut self, outlines: &[Polyline2]) {
+        let pen = self.layers.last().unwrap().pen;
+        self.hatch(outlines, &HatchOptions::solid(pen));
+    }
+
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    pub fn layer_mut(&mut self, index: usize) -> &mut Layer {
+        &mut self.layers[index]
+    }
+
+    // idrawpenplotter home (top right)
+    pub fn home(&self) -> Vec2 {
+        Vec2::new(0.0, self.view_box.width)
+    }
+
     // computes drawing distance and moving distance. sum to get total
+    // every layer starts from home since the pen is changed in between
     pub fn length(&self) -> (f32, f32) {
-        let mut drawing = 0.0;
-        let mut moving = 0.0;
-        let mut pen = Vec2::new(0.0, self.view_box.2 as f32);
-        for polyline in &self.polylines {
-            // distance from pen to first point
-            moving += pen.sub(polyline.points.first().unwrap_or(&pen)).norm();
-            drawing += polyline.length();
-            pen = *polyline.points.last().unwrap_or(&pen);
+        let home = self.home();
+        self.layers
+            .iter()
+            .map(|layer| layer.length(home))
+            .fold((0.0, 0.0), |(d0, m0), (d1, m1)| (d0 + d1, m0 + m1))
+    }
+
+    // bounding box of the content of all layers
+    pub fn bounds(&self) -> Option<ViewBox> {
+        self.layers.iter().filter_map(|layer| layer.bounds()).reduce(|a, b| a.union(&b))
+    }
+
+    // applies a 2D affine transformation to all layers
+    pub fn transform(&mut self, matrix: &Mat3) {
+        for layer in &mut self.layers {
+            layer.transform(matrix);
         }
-        (drawing, moving)
     }
 
-    // re-orders poly-lines for faster plotting
-    pub fn optimize(&mut self) {
-        // Simple greedy algorithm for the travelling salesmen
-
-        // start at idrawpenplotter home (top right)
-        let mut current = Vec2::new(0.0, self.view_box.2 as f32);
-        let mut unvisited: HashSet<usize> = HashSet::from_iter(0..self.polylines.len());
-        let mut path = Vec::new();
-        while !unvisited.is_empty() {
-            // find shortest distance to polyline start
-            let distances: Vec<_> = unvisited
-                .iter()
-                .map(|index| (self.distance_to(current, *index), *index))
-                .collect();
-            let (_, index) = distances.iter().min_by(|(a, _), (b, _)| a.total_cmp(b)).unwrap();
-            unvisited.remove(index);
-            // update path
-            path.push(*index);
-            // move current point to end (or do nothing for empty polylines)
-            current = *self.polylines[*index].points.last().unwrap_or(&current);
-        }
-
-        self.polylines = path.iter().map(|index| self.polylines[*index].clone()).collect();
-    }
-
-    pub fn save(&self, filename: &str) -> io::Result<()> {
-        let mut group = Group::new()
-            .set("fill", "none")
-            .set("stroke", "black")
-            .set("stroke-width", self.pen);
-        for polyline in &self.polylines {
-            group.append(svg::node::element::Polyline::new().set("points", as_node(&polyline)));
+    pub fn translate(&mut self, offset: Vec2) {
+        self.transform(&translation2d(&offset));
+    }
+
+    // rotates counter-clockwise in y-up coordinates, which is clockwise on
+    // the sheet since SVG has y pointing down
+    pub fn rotate(&mut self, angle: f32, center: Vec2) {
+        self.transform(&around(rotation2d(angle), center));
+    }
+
+    pub fn scale(&mut self, factor: f32, center: Vec2) {
+        self.transform(&around(scaling2d(&Vec2::repeat(factor)), center));
+    }
+
+    pub fn mirror(&mut self, mirror: Mirror, center: Vec2) {
+        let factors = match mirror {
+            Mirror::Horizontal => Vec2::new(-1.0, 1.0),
+            Mirror::Vertical => Vec2::new(1.0, -1.0),
+        };
+        self.transform(&around(scaling2d(&factors), center));
+    }
+
+    // moves the content so its bounds are centered in the area
+    pub fn center(&mut self, area: ViewBox) {
+        if let Some(bounds) = self.bounds() {
+            self.translate(area.center() - bounds.center());
+        }
+    }
+
+    // scales the content uniformly to fill the area and centers it
+    pub fn fit(&mut self, area: ViewBox) {
+        let Some(bounds) = self.bounds() else {
+            return;
+        };
+        let factor = match (bounds.width > 0.0, bounds.height > 0.0) {
+            (true, true) => (area.width / bounds.width).min(area.height / bounds.height),
+            (true, false) => area.width / bounds.width,
+            (false, true) => area.height / bounds.height,
+            (false, false) => 1.0,
+        };
+        self.scale(factor, bounds.center());
+        self.translate(area.center() - bounds.center());
+    }
+
+    // clips all layers to the drawable area, e.g. `view_box.pad(margins)`
+    pub fn clip(&mut self, area: ViewBox) {
+        for layer in &mut self.layers {
+            layer.clip(area);
+        }
+    }
+
+    // joins touching poly-lines in each layer to cut pen lifts
+    pub fn merge(&mut self, tolerance: f32) -> MergeReport {
+        let count = |paper: &Paper| paper.layers.iter().map(|l| l.polylines.len()).sum();
+        let before = count(self);
+        for layer in &mut self.layers {
+            layer.merge(tolerance);
+        }
+        MergeReport { before, after: count(self) }
+    }
+
+    // re-orders poly-lines in each layer for faster plotting
+    pub fn optimize(&mut self) -> OptimizeReport {
+        self.optimize_with(&OptimizeOptions::default())
+    }
+
+    // the time budget is shared between the layers
+    pub fn optimize_with(&mut self, options: &OptimizeOptions) -> OptimizeReport {
+        let home = self.home();
+        let (_, travel_before) = self.length();
+        let options = OptimizeOptions {
+            time_budget: options.time_budget / self.layers.len().max(1) as u32,
+            ..*options
+        };
+        for layer in &mut self.layers {
+            layer.optimize(home, &options);
         }
-        let document = Document::new()
-            .set("width", format!("{}mm", self.view_box.2))
-            .set("height", format!("{}mm", self.view_box.3))
-            .set("viewBox", self.view_box)
-            .add(group);
+        let (_, travel_after) = self.length();
+        OptimizeReport { travel_before, travel_after }
+    }
+
+    fn document(&self, format: SvgFormat) -> Document {
+        let v = self.view_box;
+        let mut document = Document::new()
+            .set("xmlns:inkscape", "http://www.inkscape.org/namespaces/inkscape")
+            .set("width", format!("{}mm", self.view_box.width))
+            .set("height", format!("{}mm", self.view_box.height))
+            .set("viewBox", (v.x, v.y, v.width, v.height));
+
+        for (index, layer) in self.layers.iter().enumerate() {
+            let mut group = Group::new()
+                .set("id", format!("layer{}", index + 1))
+                .set("inkscape:groupmode", "layer")
+                .set("inkscape:label", layer.name.as_str())
+                .set("fill", "none")
+                .set("stroke", layer.color.to_hex())
+                .set("stroke-width", layer.pen);
+            let mut dots = Vec::new();
+            for polyline in &layer.polylines {
+                // runs of dots go into one path, keeping the plotting order
+                if let [dot] = polyline.points[..] {
+                    dots.push(dot);
+                    continue;
+                }
+                if !dots.is_empty() {
+                    group.append(dots_path(&dots, format));
+                    dots.clear();
+                }
+                match format {
+                    SvgFormat::Paths { tolerance, decimals } => {
+                        let d = as_path(polyline, tolerance, decimals);
+                        group.append(svg::node::element::Path::new().set("d", d));
+                    }
+                    SvgFormat::Polylines if polyline.closed => {
+                        group.append(Polygon::new().set("points", as_node(polyline)));
+                    }
+                    SvgFormat::Polylines => {
+                        group.append(
+                            svg::node::element::Polyline::new().set("points", as_node(polyline)),
+                        );
+                    }
+                }
+            }
+            if !dots.is_empty() {
+                group.append(dots_path(&dots, format));
+            }
+            document.append(group);
+        }
+        document
+    }
 
-        svg::save(filename, &document)
+    pub fn write(&self, writer: impl Write, format: SvgFormat) -> io::Result<()> {
+        svg::write(writer, &self.document(format))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.save_as(path, SvgFormat::Polylines)
+    }
+
+    pub fn save_as(&self, path: impl AsRef<Path>, format: SvgFormat) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer, format)?;
+        writer.flush()
+    }
+}
+
+// adds polylines to the last layer, e.g. `paper.extend(font.layout(...))`
+impl Extend<Polyline2> for Paper {
+    fn extend<T: IntoIterator<Item = Polyline2>>(&mut self, polylines: T) {
+        for polyline in polylines {
+            self.add(polyline);
+        }
     }
 }
@@ -1,34 +1,257 @@
-use std::{iter::FromIterator, ops::Sub};
+use std::{cmp::Ordering, collections::BinaryHeap, iter::FromIterator, ops::Sub};
 
 //use nalgebra_glm::Vec2;
 use nalgebra_glm::TVec;
 
+// resampling and subdividing never produce more points than this, tiny
+// spacings are widened to fit
+const MAX_POINTS: usize = 1 << 20;
+
 #[derive(Clone)]
 pub struct Polyline<const N: usize> {
     pub points: Vec<TVec<f32, N>>,
+    // closed polylines return from the last point to the first
+    pub closed: bool,
 }
 
 impl<const N: usize> Polyline<N> {
     pub fn new() -> Polyline<N> {
-        Polyline { points: Vec::new() }
+        Polyline { points: Vec::new(), closed: false }
+    }
+    pub fn new_closed() -> Polyline<N> {
+        Polyline { points: Vec::new(), closed: true }
     }
     pub fn add(&mut self, point: TVec<f32, N>) {
         self.points.push(point);
     }
     pub fn length(&self) -> f32 {
-        let mut length = 0.0;
-        for i in 1..self.points.len() + 1 {
-            let pi = self.points[i % self.points.len()];
-            let pj = self.points[i - 1];
-            length += pi.sub(&pj).norm();
+        self.points_drawn()
+            .collect::<Vec<_>>()
+            .windows(2)
+            .map(|w| w[1].sub(w[0]).norm())
+            .sum()
+    }
+
+    // points in the order the pen visits them, back to the first point for
+    // closed polylines
+    pub fn points_drawn(&self) -> impl Iterator<Item = &TVec<f32, N>> {
+        let closing = match self.closed && self.points.len() > 1 {
+            true => self.points.first(),
+            false => None,
+        };
+        self.points.iter().chain(closing)
+    }
+
+    // where the pen is after drawing
+    pub fn end(&self) -> Option<&TVec<f32, N>> {
+        match self.closed {
+            true => self.points.first(),
+            false => self.points.last(),
+        }
+    }
+
+    // closed polylines are processed as an open path back to the start
+    fn unrolled(&self) -> Polyline<N> {
+        self.points_drawn().copied().collect()
+    }
+
+    // undoes `unrolled` after processing
+    fn rolled(mut self, closed: bool) -> Polyline<N> {
+        if closed && self.points.len() > 1 {
+            self.points.pop();
+            self.closed = true;
+        }
+        self
+    }
+
+    /// Ramer-Douglas-Peucker simplification. Removed points are at most
+    /// `tolerance` away from the result.
+    pub fn simplify(&self, tolerance: f32) -> Polyline<N> {
+        if self.closed {
+            return self.unrolled().simplify(tolerance).rolled(true);
+        }
+        let n = self.points.len();
+        if n < 3 {
+            return self.clone();
+        }
+        let mut keep = vec![false; n];
+        keep[0] = true;
+        keep[n - 1] = true;
+        let mut stack = vec![(0, n - 1)];
+        while let Some((first, last)) = stack.pop() {
+            let (a, b) = (&self.points[first], &self.points[last]);
+            let farthest = (first + 1..last)
+                .map(|i| (i, distance_to_segment(&self.points[i], a, b)))
+                .max_by(|(_, d0), (_, d1)| d0.total_cmp(d1));
+            if let Some((i, distance)) = farthest {
+                if distance > tolerance {
+                    keep[i] = true;
+                    stack.push((first, i));
+                    stack.push((i, last));
+                }
+            }
+        }
+        self.points.iter().zip(keep).filter(|(_, k)| *k).map(|(p, _)| *p).collect()
+    }
+
+    /// Visvalingam-Whyatt simplification. Repeatedly removes the point whose
+    /// triangle with its neighbours has the smallest area, while that area
+    /// is below `min_area`.
+    pub fn simplify_area(&self, min_area: f32) -> Polyline<N> {
+        if self.closed {
+            return self.unrolled().simplify_area(min_area).rolled(true);
+        }
+        let n = self.points.len();
+        if n < 3 {
+            return self.clone();
+        }
+        let mut previous: Vec<usize> = (0..n).map(|i| i.saturating_sub(1)).collect();
+        let mut next: Vec<usize> = (0..n).map(|i| (i + 1).min(n - 1)).collect();
+        let mut areas: Vec<_> = (0..n)
+            .map(|i| match i {
+                0 => f32::INFINITY,
+                i if i == n - 1 => f32::INFINITY,
+                i => self.triangle_area(i - 1, i, i + 1),
+            })
+            .collect();
+        let mut heap: BinaryHeap<_> = (1..n - 1).map(|i| Smallest(areas[i], i)).collect();
+        let mut removed = vec![false; n];
+        while let Some(Smallest(area, i)) = heap.pop() {
+            // skip entries that were updated later
+            if removed[i] || area != areas[i] {
+                continue;
+            }
+            if area >= min_area {
+                break;
+            }
+            removed[i] = true;
+            let (p, q) = (previous[i], next[i]);
+            next[p] = q;
+            previous[q] = p;
+            for j in [p, q] {
+                if j == 0 || j == n - 1 {
+                    continue;
+                }
+                // never let a neighbour become cheaper than the point removed
+                areas[j] = self.triangle_area(previous[j], j, next[j]).max(area);
+                heap.push(Smallest(areas[j], j));
+            }
         }
-        length
+        self.points.iter().zip(removed).filter(|(_, r)| !r).map(|(p, _)| *p).collect()
+    }
+
+    fn triangle_area(&self, a: usize, b: usize, c: usize) -> f32 {
+        let u = self.points[a] - self.points[b];
+        let v = self.points[c] - self.points[b];
+        // works in any dimension
+        0.5 * (u.norm_squared() * v.norm_squared() - u.dot(&v).powi(2)).max(0.0).sqrt()
+    }
+
+    /// Places points at equal arc length distances, as close to `spacing` as
+    /// possible while keeping both end points
+    pub fn resample(&self, spacing: f32) -> Polyline<N> {
+        if !spacing.is_finite() || spacing <= 0.0 {
+            return self.clone();
+        }
+        if self.closed {
+            return self.unrolled().resample(spacing).rolled(true);
+        }
+        let length = self.length();
+        if self.points.len() < 2 || length <= 0.0 {
+            return self.clone();
+        }
+        let count = ((length / spacing).round() as usize).clamp(1, MAX_POINTS);
+        let step = length / count as f32;
+        let mut resampled = Polyline::new();
+        resampled.add(self.points[0]);
+        // arc length at the start of the current segment
+        let mut walked = 0.0;
+        let mut segments = self.points.windows(2);
+        let mut segment = segments.next().unwrap();
+        for k in 1..count {
+            let target = k as f32 * step;
+            let mut segment_length = (segment[1] - segment[0]).norm();
+            while walked + segment_length < target {
+                match segments.next() {
+                    Some(s) => {
+                        walked += segment_length;
+                        segment = s;
+                        segment_length = (segment[1] - segment[0]).norm();
+                    }
+                    None => break,
+                }
+            }
+            let t = ((target - walked) / segment_length).clamp(0.0, 1.0);
+            resampled.add(segment[0].lerp(&segment[1], t));
+        }
+        resampled.add(*self.points.last().unwrap());
+        resampled
+    }
+
+    /// Splits every segment longer than `max_length` into equal parts
+    pub fn subdivide(&self, max_length: f32) -> Polyline<N> {
+        if !max_length.is_finite() || max_length <= 0.0 {
+            return self.clone();
+        }
+        if self.closed {
+            return self.unrolled().subdivide(max_length).rolled(true);
+        }
+        let max_length = max_length.max(self.length() / MAX_POINTS as f32);
+        let mut subdivided = Polyline::new();
+        let Some(first) = self.points.first() else {
+            return subdivided;
+        };
+        subdivided.add(*first);
+        for w in self.points.windows(2) {
+            let parts = ((w[1] - w[0]).norm() / max_length).ceil().max(1.0) as usize;
+            for k in 1..=parts {
+                subdivided.add(w[0].lerp(&w[1], k as f32 / parts as f32));
+            }
+        }
+        subdivided
+    }
+}
+
+fn distance_to_segment<const N: usize>(
+    p: &TVec<f32, N>,
+    a: &TVec<f32, N>,
+    b: &TVec<f32, N>,
+) -> f32 {
+    let ab = b - a;
+    let t = if ab.norm_squared() > 0.0 {
+        ((p - a).dot(&ab) / ab.norm_squared()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (p - (a + ab * t)).norm()
+}
+
+// min-heap entry for the Visvalingam areas
+struct Smallest(f32, usize);
+
+impl PartialEq for Smallest {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Smallest {}
+
+impl PartialOrd for Smallest {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Smallest {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0).then(other.1.cmp(&self.1))
     }
 }
 
 impl<const N: usize> FromIterator<TVec<f32, N>> for Polyline<N> {
     fn from_iter<T: IntoIterator<Item = TVec<f32, N>>>(iter: T) -> Self {
-        Polyline { points: iter.into_iter().collect() }
+        Polyline { points: iter.into_iter().collect(), closed: false }
     }
 }
 
@@ -0,0 +1,152 @@
+use std::{io, path::Path};
+
+use nalgebra_glm::Vec2;
+use tiny_skia::{Color, Paint, Pixmap, Stroke, Transform};
+
+use crate::{
+    paper::{Paper, Rgb},
+    polyline::Polyline2,
+    skia_utils::draw_polyline,
+    view_box::MM_PER_INCH,
+};
+
+pub struct PreviewOptions {
+    pub dpi: f32,
+    // draw pen-up moves between polylines
+    pub travel: bool,
+    pub travel_color: Rgb,
+    // number the start point of every polyline in drawing order
+    pub numbers: bool,
+    pub number_color: Rgb,
+}
+
+impl Default for PreviewOptions {
+    fn default() -> Self {
+        Self {
+            dpi: 150.0,
+            travel: true,
+            travel_color: Rgb(255, 0, 160),
+            numbers: false,
+            number_color: Rgb(0, 120, 255),
+        }
+    }
+}
+
+fn paint(color: Rgb, alpha: u8) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color(Color::from_rgba8(color.0, color.1, color.2, alpha));
+    paint.anti_alias = true;
+    paint
+}
+
+fn stroke(width: f32) -> Stroke {
+    Stroke {
+        width,
+        line_cap: tiny_skia::LineCap::Round,
+        ..Stroke::default()
+    }
+}
+
+fn segment(a: Vec2, b: Vec2) -> Polyline2 {
+    [a, b].into_iter().collect()
+}
+
+// seven segment digits, bits are segments a to g
+const DIGITS: [u8; 10] = [
+    0b0111111, 0b0000110, 0b1011011, 0b1001111, 0b1100110, 0b1101101, 0b1111101, 0b0000111,
+    0b1111111, 0b1101111,
+];
+
+// segment end points in a 0.5 x 1 cell, y pointing down
+const SEGMENTS: [((f32, f32), (f32, f32)); 7] = [
+    ((0.0, 0.0), (0.5, 0.0)),
+    ((0.5, 0.0), (0.5, 0.5)),
+    ((0.5, 0.5), (0.5, 1.0)),
+    ((0.0, 1.0), (0.5, 1.0)),
+    ((0.0, 0.5), (0.0, 1.0)),
+    ((0.0, 0.0), (0.0, 0.5)),
+    ((0.0, 0.5), (0.5, 0.5)),
+];
+
+// strokes of a number written with its top left corner at `origin`
+fn number_strokes(number: usize, origin: Vec2, height: f32) -> Vec<Polyline2> {
+    let mut strokes = Vec::new();
+    for (i, digit) in number.to_string().bytes().enumerate() {
+        let bits = DIGITS[(digit - b'0') as usize];
+        let offset = origin + Vec2::new(0.8 * height * i as f32, 0.0);
+        for (segment_index, ((x0, y0), (x1, y1))) in SEGMENTS.iter().enumerate() {
+            if bits & (1 << segment_index) != 0 {
+                let a = offset + Vec2::new(*x0, *y0) * height;
+                let b = offset + Vec2::new(*x1, *y1) * height;
+                strokes.push(segment(a, b));
+            }
+        }
+    }
+    strokes
+}
+
+/// Rasterizes the paper as the plotter would draw it. Polylines are stroked
+/// with the pen width and color of their layer, in millimetres scaled to the
+/// DPI. Travel moves and start numbers follow the current polyline order, so
+/// call this after `optimize`.
+pub fn render_preview(paper: &Paper, options: &PreviewOptions) -> io::Result<Pixmap> {
+    let view_box = paper.view_box;
+    let (width, height) = view_box.to_pixels(options.dpi);
+    let mut pixmap = Pixmap::new(width.max(1), height.max(1))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "preview size is zero"))?;
+    pixmap.fill(Color::WHITE);
+
+    let scale = options.dpi / MM_PER_INCH;
+    let transform = Transform::from_translate(-view_box.x, -view_box.y).post_scale(scale, scale);
+    // one pixel wide regardless of the DPI
+    let hairline = 1.0 / scale;
+
+    for layer in paper.layers() {
+        let pen = paint(layer.color, 255);
+        for polyline in layer.polylines() {
+            draw_polyline(&mut pixmap, polyline, &pen, &stroke(layer.pen), transform);
+        }
+    }
+
+    let travel = paint(options.travel_color, 200);
+    let number = paint(options.number_color, 255);
+    // at least 8 pixels and 1 mm high
+    let number_height = (8.0 / scale).max(1.0);
+    let mut count = 0;
+    for layer in paper.layers() {
+        // every layer starts at home after the pen change
+        let mut pen = paper.home();
+        for polyline in layer.polylines() {
+            let Some(&start) = polyline.points.first() else {
+                continue;
+            };
+            if options.travel && start != pen {
+                draw_polyline(
+                    &mut pixmap,
+                    &segment(pen, start),
+                    &travel,
+                    &stroke(hairline),
+                    transform,
+                );
+            }
+            if options.numbers {
+                count += 1;
+                let origin = start + Vec2::repeat(0.3 * number_height);
+                for digit in number_strokes(count, origin, number_height) {
+                    draw_polyline(&mut pixmap, &digit, &number, &stroke(hairline), transform);
+                }
+            }
+            pen = *polyline.end().unwrap_or(&pen);
+        }
+    }
+    Ok(pixmap)
+}
+
+pub fn save_preview(
+    paper: &Paper,
+    path: impl AsRef<Path>,
+    options: &PreviewOptions,
+) -> io::Result<()> {
+    let pixmap = render_preview(paper, options)?;
+    pixmap.save_png(path).map_err(io::Error::other)
+}
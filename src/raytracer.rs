@@ -15,6 +15,10 @@ pub struct Ray {
 }
 
 impl Ray {
+    pub fn new(origin: Vec3, direction: Vec3) -> Ray {
+        Ray { origin, direction: direction.normalize() }
+    }
+
     fn at(&self, t: f32) -> Vec3 {
         self.origin.add(self.direction.scale(t))
     }
@@ -37,8 +41,14 @@ pub fn backproject(screen: &Vec2, model: &Mat4, projection: &Mat4, viewport: Vec
     Ray { origin: eye, direction: world.sub(eye).normalize() }
 }
 
+// sphere tracing stops this close to the surface
+const SPHERE_TRACE_EPSILON: f32 = 1e-4;
+
 impl Tracer {
     pub fn trace<S: SDF>(&self, ray: &Ray, surface: &S) -> Option<Vec3> {
+        if surface.is_distance_bound() {
+            return self.sphere_trace(ray, surface);
+        }
         // first linesearch to find rough estimate
         let f = |t| surface.sdf(&ray.at(t));
         if let Some((lo, hi)) = linesearch(f, self.near, self.far, self.steps) {
@@ -50,4 +60,22 @@ impl Tracer {
         }
         None
     }
+
+    // Steps along the ray by the distance to the surface, which cannot pass
+    // through surfaces however thin their inside is. Gives up after `steps`
+    // steps, like rays grazing the surface.
+    fn sphere_trace<S: SDF>(&self, ray: &Ray, surface: &S) -> Option<Vec3> {
+        let mut t = self.near;
+        for _ in 0..self.steps {
+            let distance = surface.sdf(&ray.at(t));
+            if distance < SPHERE_TRACE_EPSILON {
+                return Some(ray.at(t));
+            }
+            t += distance;
+            if t > self.far {
+                break;
+            }
+        }
+        None
+    }
 }
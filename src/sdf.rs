@@ -2,4 +2,10 @@ use nalgebra_glm::Vec3;
 
 pub trait SDF {
     fn sdf(&self, position: &Vec3) -> f32;
+
+    // true when the level never exceeds the distance to the surface, so rays
+    // can step by it without passing through
+    fn is_distance_bound(&self) -> bool {
+        false
+    }
 }
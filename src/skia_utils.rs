@@ -17,6 +17,13 @@ pub fn draw_polyline(
             pb.line_to(point.x, point.y);
         }
     }
+    // a dot is drawn as the round cap of a zero length line
+    if let [point] = polyline.points[..] {
+        pb.line_to(point.x, point.y);
+    }
+    if polyline.closed {
+        pb.close();
+    }
     if let Some(path) = pb.finish() {
         pixmap.stroke_path(&path, &paint, &stroke, transform, None);
     }
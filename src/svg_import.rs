@@ -0,0 +1,691 @@
+use std::{
+    f32::consts::TAU,
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use nalgebra_glm::{Mat3, Vec2, Vec3};
+use svg::{
+    node::{
+        element::{
+            path::{Command, Data, Position},
+            tag::Type,
+        },
+        Attributes,
+    },
+    parser::Event,
+    Parser,
+};
+
+use crate::{
+    bezier::CubicBezier,
+    paper::{Paper, Rgb},
+    polyline::Polyline2,
+    view_box::ViewBox,
+};
+
+pub struct SvgLayer {
+    pub name: String,
+    pub pen: Option<f32>,
+    pub color: Option<Rgb>,
+    pub polylines: Vec<Polyline2>,
+}
+
+/// Polylines read from an SVG document, in the units of its viewBox
+pub struct SvgDrawing {
+    pub view_box: ViewBox,
+    pub layers: Vec<SvgLayer>,
+}
+
+impl SvgDrawing {
+    pub fn polylines(&self) -> impl Iterator<Item = &Polyline2> {
+        self.layers.iter().flat_map(|layer| layer.polylines.iter())
+    }
+
+    // Inkscape layers become paper layers, layers without a stroke width use `pen`
+    pub fn into_paper(self, pen: f32) -> Paper {
+        let mut paper = Paper::new(self.view_box, pen);
+        for (index, layer) in self.layers.into_iter().enumerate() {
+            let layer_pen = layer.pen.unwrap_or(pen);
+            let color = layer.color.unwrap_or(Rgb::BLACK);
+            let target = if index == 0 {
+                let first = paper.layer_mut(0);
+                first.name = layer.name;
+                first.pen = layer_pen;
+                first.color = color;
+                0
+            } else {
+                paper.add_layer(&layer.name, layer_pen, color)
+            };
+            for polyline in layer.polylines {
+                paper.layer_mut(target).add(polyline);
+            }
+        }
+        paper
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message.into())
+}
+
+fn parse_numbers(text: &str) -> io::Result<Vec<f32>> {
+    // handles "1,2 3-4" and "1e-3.5" style lists
+    let mut numbers = Vec::new();
+    let mut token = String::new();
+    let flush = |token: &mut String, numbers: &mut Vec<f32>| -> io::Result<()> {
+        if !token.is_empty() {
+            let value = token.parse().map_err(|_| invalid_data(format!("bad number `{token}`")))?;
+            numbers.push(value);
+            token.clear();
+        }
+        Ok(())
+    };
+    for c in text.chars() {
+        let exponent = token.ends_with(['e', 'E']);
+        if c == ',' || c.is_whitespace() {
+            flush(&mut token, &mut numbers)?;
+        } else if ((c == '-' || c == '+') && !exponent)
+            || (c == '.' && token.contains(['.', 'e', 'E']))
+        {
+            // a sign, a second decimal point or one after an exponent starts
+            // a new number
+            flush(&mut token, &mut numbers)?;
+            token.push(c);
+        } else {
+            token.push(c);
+        }
+    }
+    flush(&mut token, &mut numbers)?;
+    Ok(numbers)
+}
+
+// parses a length like "210mm" or "12.5", ignoring the unit
+fn parse_length(text: &str) -> Option<f32> {
+    let end = text
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E')))
+        .unwrap_or(text.len());
+    text[..end].parse().ok()
+}
+
+fn attribute(attributes: &Attributes, name: &str) -> Option<f32> {
+    attributes.get(name).and_then(|value| parse_length(value))
+}
+
+fn parse_color(text: &str) -> Option<Rgb> {
+    let hex = text.trim().strip_prefix('#')?;
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    match hex.len() {
+        6 => Some(Rgb(channel(0)?, channel(2)?, channel(4)?)),
+        3 => {
+            let short = |i: usize| u8::from_str_radix(hex.get(i..i + 1)?, 16).ok().map(|v| v * 17);
+            Some(Rgb(short(0)?, short(1)?, short(2)?))
+        }
+        _ => None,
+    }
+}
+
+#[rustfmt::skip]
+fn parse_transform(text: &str) -> io::Result<Mat3> {
+    let mut transform = Mat3::identity();
+    for part in text.split(')') {
+        let Some((name, arguments)) = part.split_once('(') else {
+            continue;
+        };
+        let name = name.trim().trim_start_matches(',').trim();
+        let a = parse_numbers(arguments)?;
+        let arg = |i: usize| a.get(i).copied();
+        let matrix = match (name, a.len()) {
+            ("matrix", 6) => Mat3::new(
+                a[0], a[2], a[4],
+                a[1], a[3], a[5],
+                0.0, 0.0, 1.0,
+            ),
+            ("translate", 1 | 2) => Mat3::new(
+                1.0, 0.0, a[0],
+                0.0, 1.0, arg(1).unwrap_or(0.0),
+                0.0, 0.0, 1.0,
+            ),
+            ("scale", 1 | 2) => Mat3::new(
+                a[0], 0.0, 0.0,
+                0.0, arg(1).unwrap_or(a[0]), 0.0,
+                0.0, 0.0, 1.0,
+            ),
+            ("rotate", 1 | 3) => {
+                let (s, c) = a[0].to_radians().sin_cos();
+                let (cx, cy) = (arg(1).unwrap_or(0.0), arg(2).unwrap_or(0.0));
+                // rotate around (cx, cy)
+                Mat3::new(
+                    c, -s, cx - c * cx + s * cy,
+                    s, c, cy - s * cx - c * cy,
+                    0.0, 0.0, 1.0,
+                )
+            }
+            ("skewX", 1) => Mat3::new(
+                1.0, a[0].to_radians().tan(), 0.0,
+                0.0, 1.0, 0.0,
+                0.0, 0.0, 1.0,
+            ),
+            ("skewY", 1) => Mat3::new(
+                1.0, 0.0, 0.0,
+                a[0].to_radians().tan(), 1.0, 0.0,
+                0.0, 0.0, 1.0,
+            ),
+            _ => return Err(invalid_data(format!("unsupported transform `{}`", part.trim()))),
+        };
+        transform *= matrix;
+    }
+    Ok(transform)
+}
+
+// number of segments needed to keep a circular arc within tolerance
+fn arc_segments(radius: f32, angle: f32, tolerance: f32) -> usize {
+    let ratio = (1.0 - tolerance / radius.max(f32::EPSILON)).clamp(-1.0, 1.0);
+    let step = 2.0 * ratio.acos();
+    if step <= f32::EPSILON {
+        return 64;
+    }
+    ((angle.abs() / step).ceil() as usize).clamp(1, 4096)
+}
+
+fn ellipse_point(center: Vec2, radii: Vec2, rotation: f32, theta: f32) -> Vec2 {
+    let (sin_r, cos_r) = rotation.sin_cos();
+    let (sin_t, cos_t) = theta.sin_cos();
+    Vec2::new(
+        center.x + radii.x * cos_t * cos_r - radii.y * sin_t * sin_r,
+        center.y + radii.x * cos_t * sin_r + radii.y * sin_t * cos_r,
+    )
+}
+
+fn vector_angle(u: Vec2, v: Vec2) -> f32 {
+    (u.x * v.y - u.y * v.x).atan2(u.dot(&v))
+}
+
+// endpoint to center parameterization from the SVG implementation notes
+#[allow(clippy::too_many_arguments)]
+fn flatten_arc(
+    from: Vec2,
+    to: Vec2,
+    radii: Vec2,
+    rotation_degrees: f32,
+    large_arc: bool,
+    sweep: bool,
+    tolerance: f32,
+    points: &mut Vec<Vec2>,
+) {
+    let (mut rx, mut ry) = (radii.x.abs(), radii.y.abs());
+    if (to - from).norm() < f32::EPSILON {
+        return;
+    }
+    if rx < f32::EPSILON || ry < f32::EPSILON {
+        points.push(to);
+        return;
+    }
+    let phi = rotation_degrees.to_radians();
+    let (sin_phi, cos_phi) = phi.sin_cos();
+    let half = (from - to) * 0.5;
+    let x1 = cos_phi * half.x + sin_phi * half.y;
+    let y1 = -sin_phi * half.x + cos_phi * half.y;
+
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+    let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let sign = if large_arc != sweep { 1.0 } else { -1.0 };
+    let coefficient = sign * (numerator / denominator).max(0.0).sqrt();
+    let cx1 = coefficient * rx * y1 / ry;
+    let cy1 = -coefficient * ry * x1 / rx;
+    let middle = (from + to) * 0.5;
+    let center = Vec2::new(
+        cos_phi * cx1 - sin_phi * cy1 + middle.x,
+        sin_phi * cx1 + cos_phi * cy1 + middle.y,
+    );
+
+    let u = Vec2::new((x1 - cx1) / rx, (y1 - cy1) / ry);
+    let v = Vec2::new((-x1 - cx1) / rx, (-y1 - cy1) / ry);
+    let theta = vector_angle(Vec2::new(1.0, 0.0), u);
+    let mut delta = vector_angle(u, v) % TAU;
+    if !sweep && delta > 0.0 {
+        delta -= TAU;
+    } else if sweep && delta < 0.0 {
+        delta += TAU;
+    }
+
+    let n = arc_segments(rx.max(ry), delta, tolerance);
+    for i in 1..n {
+        let t = theta + delta * i as f32 / n as f32;
+        points.push(ellipse_point(center, Vec2::new(rx, ry), phi, t));
+    }
+    points.push(to);
+}
+
+fn flatten_ellipse(center: Vec2, radii: Vec2, tolerance: f32) -> Polyline2 {
+    let n = arc_segments(radii.x.max(radii.y), TAU, tolerance).max(3);
+    Polyline2 {
+        points: (0..n)
+            .map(|i| ellipse_point(center, radii, 0.0, TAU * i as f32 / n as f32))
+            .collect(),
+        closed: true,
+    }
+}
+
+struct PathBuilder {
+    tolerance: f32,
+    subpaths: Vec<Polyline2>,
+    current: Vec<Vec2>,
+    start: Vec2,
+    position: Vec2,
+    // last control point for the smooth curve commands
+    cubic_control: Option<Vec2>,
+    quadratic_control: Option<Vec2>,
+}
+
+impl PathBuilder {
+    fn new(tolerance: f32) -> Self {
+        Self {
+            tolerance,
+            subpaths: Vec::new(),
+            current: Vec::new(),
+            start: Vec2::zeros(),
+            position: Vec2::zeros(),
+            cubic_control: None,
+            quadratic_control: None,
+        }
+    }
+
+    fn finish_subpath(&mut self, closed: bool) {
+        let points = std::mem::take(&mut self.current);
+        if points.len() >= 2 {
+            self.subpaths.push(Polyline2 { points, closed });
+        }
+    }
+
+    fn move_to(&mut self, point: Vec2) {
+        self.finish_subpath(false);
+        self.current.push(point);
+        self.start = point;
+        self.position = point;
+    }
+
+    fn line_to(&mut self, point: Vec2) {
+        if self.current.is_empty() {
+            self.current.push(self.position);
+        }
+        self.current.push(point);
+        self.position = point;
+    }
+
+    fn cubic_to(&mut self, p1: Vec2, p2: Vec2, p3: Vec2) {
+        if self.current.is_empty() {
+            self.current.push(self.position);
+        }
+        CubicBezier::new(self.position, p1, p2, p3).flatten_into(self.tolerance, &mut self.current);
+        self.position = p3;
+    }
+
+    fn close(&mut self) {
+        // the closing segment is implied by the closed flag
+        if self.current.len() > 2 && self.current.last() == Some(&self.start) {
+            self.current.pop();
+        }
+        self.finish_subpath(true);
+        self.position = self.start;
+    }
+
+    fn apply(&mut self, command: &Command) -> io::Result<()> {
+        let relative = |position: &Position| matches!(position, Position::Relative);
+        let (cubic_control, quadratic_control) = (self.cubic_control, self.quadratic_control);
+        self.cubic_control = None;
+        self.quadratic_control = None;
+        match command {
+            Command::Move(position, parameters) => {
+                for (i, pair) in parameters.chunks_exact(2).enumerate() {
+                    let offset = if relative(position) {
+                        self.position
+                    } else {
+                        Vec2::zeros()
+                    };
+                    let point = offset + Vec2::new(pair[0], pair[1]);
+                    // following pairs are implicit line-to commands
+                    if i == 0 {
+                        self.move_to(point);
+                    } else {
+                        self.line_to(point);
+                    }
+                }
+            }
+            Command::Line(position, parameters) => {
+                for pair in parameters.chunks_exact(2) {
+                    let offset = if relative(position) {
+                        self.position
+                    } else {
+                        Vec2::zeros()
+                    };
+                    self.line_to(offset + Vec2::new(pair[0], pair[1]));
+                }
+            }
+            Command::HorizontalLine(position, parameters) => {
+                for &x in parameters.iter() {
+                    let x = if relative(position) {
+                        self.position.x + x
+                    } else {
+                        x
+                    };
+                    self.line_to(Vec2::new(x, self.position.y));
+                }
+            }
+            Command::VerticalLine(position, parameters) => {
+                for &y in parameters.iter() {
+                    let y = if relative(position) {
+                        self.position.y + y
+                    } else {
+                        y
+                    };
+                    self.line_to(Vec2::new(self.position.x, y));
+                }
+            }
+            Command::CubicCurve(position, parameters) => {
+                for p in parameters.chunks_exact(6) {
+                    let offset = if relative(position) {
+                        self.position
+                    } else {
+                        Vec2::zeros()
+                    };
+                    let p1 = offset + Vec2::new(p[0], p[1]);
+                    let p2 = offset + Vec2::new(p[2], p[3]);
+                    let p3 = offset + Vec2::new(p[4], p[5]);
+                    self.cubic_to(p1, p2, p3);
+                    self.cubic_control = Some(p2);
+                }
+            }
+            Command::SmoothCubicCurve(position, parameters) => {
+                let mut previous = cubic_control;
+                for p in parameters.chunks_exact(4) {
+                    let offset = if relative(position) {
+                        self.position
+                    } else {
+                        Vec2::zeros()
+                    };
+                    let p1 = previous.map_or(self.position, |c| 2.0 * self.position - c);
+                    let p2 = offset + Vec2::new(p[0], p[1]);
+                    let p3 = offset + Vec2::new(p[2], p[3]);
+                    self.cubic_to(p1, p2, p3);
+                    previous = Some(p2);
+                }
+                self.cubic_control = previous;
+            }
+            Command::QuadraticCurve(position, parameters) => {
+                for p in parameters.chunks_exact(4) {
+                    let offset = if relative(position) {
+                        self.position
+                    } else {
+                        Vec2::zeros()
+                    };
+                    let control = offset + Vec2::new(p[0], p[1]);
+                    let end = offset + Vec2::new(p[2], p[3]);
+                    let curve = CubicBezier::from_quadratic(self.position, control, end);
+                    self.cubic_to(curve.p1, curve.p2, curve.p3);
+                    self.quadratic_control = Some(control);
+                }
+            }
+            Command::SmoothQuadraticCurve(position, parameters) => {
+                let mut previous = quadratic_control;
+                for p in parameters.chunks_exact(2) {
+                    let offset = if relative(position) {
+                        self.position
+                    } else {
+                        Vec2::zeros()
+                    };
+                    let control = previous.map_or(self.position, |c| 2.0 * self.position - c);
+                    let end = offset + Vec2::new(p[0], p[1]);
+                    let curve = CubicBezier::from_quadratic(self.position, control, end);
+                    self.cubic_to(curve.p1, curve.p2, curve.p3);
+                    previous = Some(control);
+                }
+                self.quadratic_control = previous;
+            }
+            Command::EllipticalArc(position, parameters) => {
+                for p in parameters.chunks_exact(7) {
+                    let offset = if relative(position) {
+                        self.position
+                    } else {
+                        Vec2::zeros()
+                    };
+                    let end = offset + Vec2::new(p[5], p[6]);
+                    if self.current.is_empty() {
+                        self.current.push(self.position);
+                    }
+                    let (from, radii) = (self.position, Vec2::new(p[0], p[1]));
+                    let (large_arc, sweep) = (p[3] != 0.0, p[4] != 0.0);
+                    let tolerance = self.tolerance;
+                    flatten_arc(
+                        from,
+                        end,
+                        radii,
+                        p[2],
+                        large_arc,
+                        sweep,
+                        tolerance,
+                        &mut self.current,
+                    );
+                    self.position = end;
+                }
+            }
+            Command::Close => self.close(),
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Vec<Polyline2> {
+        self.finish_subpath(false);
+        self.subpaths
+    }
+}
+
+fn flatten_path(data: &str, tolerance: f32) -> io::Result<Vec<Polyline2>> {
+    let data = Data::parse(data).map_err(|error| invalid_data(error.to_string()))?;
+    let mut builder = PathBuilder::new(tolerance);
+    for command in data.iter() {
+        builder.apply(command)?;
+    }
+    Ok(builder.finish())
+}
+
+fn rect_path(attributes: &Attributes) -> Option<String> {
+    let x = attribute(attributes, "x").unwrap_or(0.0);
+    let y = attribute(attributes, "y").unwrap_or(0.0);
+    let w = attribute(attributes, "width")?;
+    let h = attribute(attributes, "height")?;
+    let rx = attribute(attributes, "rx");
+    let ry = attribute(attributes, "ry").or(rx).unwrap_or(0.0).min(h / 2.0);
+    let rx = rx.unwrap_or(ry).min(w / 2.0);
+    if rx <= 0.0 || ry <= 0.0 {
+        return Some(format!("M{x},{y} h{w} v{h} h{} Z", -w));
+    }
+    let (iw, ih) = (w - 2.0 * rx, h - 2.0 * ry);
+    Some(format!(
+        "M{},{y} h{iw} a{rx},{ry} 0 0 1 {rx},{ry} v{ih} a{rx},{ry} 0 0 1 {},{ry} h{} \
+         a{rx},{ry} 0 0 1 {},{} v{} a{rx},{ry} 0 0 1 {rx},{} Z",
+        x + rx,
+        -rx,
+        -iw,
+        -rx,
+        -ry,
+        -ih,
+        -ry
+    ))
+}
+
+// flattens a single shape element into point lists in its local coordinates
+fn shape_points(name: &str, attributes: &Attributes, tolerance: f32) -> io::Result<Vec<Polyline2>> {
+    let points = |closed: bool| -> io::Result<Polyline2> {
+        let numbers = parse_numbers(attributes.get("points").map(|v| &**v).unwrap_or(""))?;
+        let points = numbers.chunks_exact(2).map(|p| Vec2::new(p[0], p[1])).collect();
+        Ok(Polyline2 { points, closed })
+    };
+    let shapes = match name {
+        "polyline" => vec![points(false)?],
+        "polygon" => vec![points(true)?],
+        "line" => {
+            let get = |key| attribute(attributes, key).unwrap_or(0.0);
+            vec![[
+                Vec2::new(get("x1"), get("y1")),
+                Vec2::new(get("x2"), get("y2")),
+            ]
+            .into_iter()
+            .collect()]
+        }
+        "rect" => match rect_path(attributes) {
+            Some(data) => flatten_path(&data, tolerance)?,
+            None => Vec::new(),
+        },
+        "circle" | "ellipse" => {
+            let center = Vec2::new(
+                attribute(attributes, "cx").unwrap_or(0.0),
+                attribute(attributes, "cy").unwrap_or(0.0),
+            );
+            let radii = match name {
+                "circle" => Vec2::repeat(attribute(attributes, "r").unwrap_or(0.0)),
+                _ => Vec2::new(
+                    attribute(attributes, "rx").unwrap_or(0.0),
+                    attribute(attributes, "ry").unwrap_or(0.0),
+                ),
+            };
+            if radii.x <= 0.0 || radii.y <= 0.0 {
+                Vec::new()
+            } else {
+                vec![flatten_ellipse(center, radii, tolerance)]
+            }
+        }
+        "path" => match attributes.get("d") {
+            Some(data) => flatten_path(data, tolerance)?,
+            None => Vec::new(),
+        },
+        _ => Vec::new(),
+    };
+    Ok(shapes.into_iter().filter(|shape| shape.points.len() >= 2).collect())
+}
+
+fn transform_point(transform: &Mat3, point: &Vec2) -> Vec2 {
+    let p = transform * Vec3::new(point.x, point.y, 1.0);
+    Vec2::new(p.x, p.y)
+}
+
+fn is_shape(name: &str) -> bool {
+    matches!(name, "polyline" | "polygon" | "line" | "rect" | "circle" | "ellipse" | "path")
+}
+
+// content of these elements is not drawn directly
+fn is_hidden_container(name: &str) -> bool {
+    matches!(
+        name,
+        "defs" | "clipPath" | "mask" | "symbol" | "marker" | "pattern" | "metadata"
+    )
+}
+
+struct Frame {
+    transform: Mat3,
+    hidden: bool,
+}
+
+/// Reads all drawable shapes from an SVG document. Curves and arcs are
+/// flattened so no point deviates more than `tolerance` viewBox units.
+pub fn read_svg(content: &str, tolerance: f32) -> io::Result<SvgDrawing> {
+    let mut view_box = None;
+    let mut stack: Vec<Frame> = vec![Frame { transform: Mat3::identity(), hidden: false }];
+    let mut layers: Vec<SvgLayer> = Vec::new();
+    // shapes outside of any Inkscape layer
+    let mut default_layer = None;
+    // stack depth and index into layers for each open layer group
+    let mut layer_stack: Vec<(usize, usize)> = Vec::new();
+
+    for event in Parser::new(content) {
+        let (tag, kind, attributes) = match event {
+            Event::Tag(tag, kind, attributes) => (tag, kind, attributes),
+            Event::Error(error) => return Err(invalid_data(error.to_string())),
+            _ => continue,
+        };
+        let name = tag.rsplit(':').next().unwrap_or(tag);
+        if kind == Type::End {
+            stack.pop();
+            if layer_stack.last().is_some_and(|&(depth, _)| depth == stack.len()) {
+                layer_stack.pop();
+            }
+            continue;
+        }
+
+        let parent = stack.last().unwrap();
+        let local = match attributes.get("transform") {
+            Some(text) => parse_transform(text)?,
+            None => Mat3::identity(),
+        };
+        let transform = parent.transform * local;
+        let hidden = parent.hidden
+            || is_hidden_container(name)
+            || attributes.get("display").is_some_and(|value| &**value == "none");
+
+        if name == "svg" && view_box.is_none() {
+            view_box = match attributes.get("viewBox") {
+                Some(text) => match parse_numbers(text)?[..] {
+                    [x, y, w, h] => Some(ViewBox::new(x, y, w, h)),
+                    _ => return Err(invalid_data("viewBox needs four numbers")),
+                },
+                None => Some(ViewBox::size(
+                    attribute(&attributes, "width").unwrap_or(0.0),
+                    attribute(&attributes, "height").unwrap_or(0.0),
+                )),
+            };
+        }
+
+        let is_layer = name == "g"
+            && attributes.get("inkscape:groupmode").is_some_and(|mode| &**mode == "layer");
+        if is_layer && kind == Type::Start {
+            let label = attributes.get("inkscape:label").or_else(|| attributes.get("id"));
+            let layer_name =
+                label.map_or_else(|| format!("Layer {}", layers.len() + 1), |l| l.to_string());
+            layers.push(SvgLayer {
+                name: layer_name,
+                pen: attribute(&attributes, "stroke-width"),
+                color: attributes.get("stroke").and_then(|value| parse_color(value)),
+                polylines: Vec::new(),
+            });
+            layer_stack.push((stack.len(), layers.len() - 1));
+        }
+
+        if is_shape(name) && !hidden {
+            let scale = transform.fixed_view::<2, 2>(0, 0).determinant().abs().sqrt();
+            let local_tolerance = tolerance / scale.max(f32::EPSILON);
+            let target = match layer_stack.last() {
+                Some(&(_, index)) => index,
+                None => *default_layer.get_or_insert_with(|| {
+                    layers.push(SvgLayer {
+                        name: format!("Layer {}", layers.len() + 1),
+                        pen: None,
+                        color: None,
+                        polylines: Vec::new(),
+                    });
+                    layers.len() - 1
+                }),
+            };
+            for shape in shape_points(name, &attributes, local_tolerance)? {
+                let points = shape.points.iter().map(|p| transform_point(&transform, p)).collect();
+                layers[target].polylines.push(Polyline2 { points, closed: shape.closed });
+            }
+        }
+
+        if kind == Type::Start {
+            stack.push(Frame { transform, hidden });
+        }
+    }
+
+    let view_box = view_box.ok_or_else(|| invalid_data("missing <svg> element"))?;
+    Ok(SvgDrawing { view_box, layers })
+}
+
+pub fn load_svg(path: impl AsRef<Path>, tolerance: f32) -> io::Result<SvgDrawing> {
+    read_svg(&fs::read_to_string(path)?, tolerance)
+}
@@ -0,0 +1,53 @@
+use std::f32::consts::FRAC_PI_2;
+
+use nalgebra_glm::Vec2;
+
+use crate::{
+    finite_difference::check_derivatives,
+    geometries::{
+        algebra::Function, gaussian::Gaussian, heightmap::Heightmap, hole::Hole, pulse::Pulse,
+    },
+    geometry::{compute_gamma, DifferentiableGeometry, Geometry},
+};
+
+fn pulse() -> Pulse {
+    Pulse {
+        amplitude: 0.2,
+        sigma: 1.5,
+        c: 1.0,
+        lambda: 0.3,
+        cycles: 1.0,
+        t: 0.5,
+    }
+}
+
+#[test]
+fn sums_and_scales_keep_derivatives() {
+    let surface = Hole.plus(pulse()).scaled(0.5);
+    let mismatches = check_derivatives(&surface, Vec2::new(0.5, 0.4), Vec2::new(2.0, 1.8), 5, 1e-2);
+    assert!(mismatches.is_empty(), "{mismatches:?}");
+}
+
+#[test]
+fn products_maps_and_rotations_keep_derivatives() {
+    let surface = Gaussian
+        .translated(Vec2::new(1.0, 0.5))
+        .rotated(0.7)
+        .times(pulse().map(Function::Exp))
+        .minus(Gaussian.map(Function::Sin));
+    let mismatches = check_derivatives(&surface, Vec2::new(0.5, 0.4), Vec2::new(2.0, 1.8), 5, 1e-2);
+    assert!(mismatches.is_empty(), "{mismatches:?}");
+}
+
+#[test]
+fn moving_and_turning_moves_the_peak() {
+    let peak = Gaussian.translated(Vec2::new(1.0, 0.0)).rotated(FRAC_PI_2);
+    assert!((peak.z(&Vec2::new(0.0, 1.0)) - 1.0).abs() < 1e-6);
+    assert!(peak.du().evaluate(&Vec2::new(0.0, 1.0)).z.abs() < 1e-6);
+}
+
+#[test]
+fn layered_surfaces_have_geodesics_without_an_adapter() {
+    let gamma = compute_gamma(&Hole.plus(pulse()), &Vec2::new(1.2, 0.3));
+    assert!(gamma.iter().flatten().flatten().all(|g| g.is_finite()));
+}
@@ -0,0 +1,108 @@
+use nalgebra_glm::{Vec2, Vec3};
+
+use crate::{
+    autodiff::{jets, Jet, Parametric, Scalar},
+    geometries::{sphere::Sphere, torus::Torus},
+    geometry::{compute_gamma, DifferentiableGeometry, Geometry},
+};
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4 * (1.0 + b.abs())
+}
+
+fn close3(a: &Vec3, b: &Vec3) -> bool {
+    (a - b).norm() < 1e-4 * (1.0 + b.norm())
+}
+
+#[test]
+fn jets_follow_chain_and_product_rules() {
+    let p = Vec2::new(0.7, -1.3);
+    let (u, v) = Jet::variables(&p);
+    // f = u² v + sin(u v) / v + sqrt(exp(u))
+    let f = u.powi(2) * v + (u * v).sin() / v + u.exp().sqrt();
+    let (x, y) = (p.x, p.y);
+    let e = (0.5 * x).exp();
+    let (s, c) = (x * y).sin_cos();
+    assert!(close(f.value, x * x * y + s / y + e));
+    assert!(close(f.gradient.x, 2.0 * x * y + c + 0.5 * e));
+    assert!(close(f.gradient.y, x * x + x * c / y - s / (y * y)));
+    assert!(close(f.hessian[(0, 0)], 2.0 * y - y * s + 0.25 * e));
+    assert!(close(f.hessian[(0, 1)], 2.0 * x - x * s));
+    assert!(close(f.hessian[(1, 0)], f.hessian[(0, 1)]));
+    assert!(close(
+        f.hessian[(1, 1)],
+        -x * x * s / y - 2.0 * x * c / (y * y) + 2.0 * s / (y * y * y)
+    ));
+
+    // f32 evaluates the same expression without derivatives
+    let g = |u: f32, v: f32| u.powi(2) * v + Scalar::sin(u * v) / v + Scalar::sqrt(Scalar::exp(u));
+    assert!(close(g(x, y), f.value));
+}
+
+#[test]
+fn torus_derivatives_match_the_analytic_ones() {
+    let torus = Torus::new(0.5, 2.0);
+    let (r, big) = (torus.radius_minor, torus.radius_major);
+    for p in [
+        Vec2::new(0.3, 1.1),
+        Vec2::new(-2.0, 0.4),
+        Vec2::new(3.0, -2.5),
+    ] {
+        let (su, cu) = p.x.sin_cos();
+        let (sv, cv) = p.y.sin_cos();
+        let ring = big + r * cu;
+        assert!(close3(&torus.evaluate(&p), &Vec3::new(ring * cv, ring * sv, r * su)));
+        assert!(close3(&torus.du().evaluate(&p), &Vec3::new(-r * su * cv, -r * su * sv, r * cu)));
+        assert!(close3(&torus.dv().evaluate(&p), &Vec3::new(-ring * sv, ring * cv, 0.0)));
+        let dudu = Vec3::new(-r * cu * cv, -r * cu * sv, -r * su);
+        assert!(close3(&torus.du().du().evaluate(&p), &dudu));
+        let dudv = Vec3::new(r * su * sv, -r * su * cv, 0.0);
+        assert!(close3(&torus.du().dv().evaluate(&p), &dudv));
+        assert!(close3(&torus.dv().du().evaluate(&p), &dudv));
+        assert!(close3(&torus.dv().dv().evaluate(&p), &Vec3::new(-ring * cv, -ring * sv, 0.0)));
+    }
+}
+
+// a sphere written only once, without an analytic metric
+struct Ball;
+
+impl Parametric for Ball {
+    fn point<S: Scalar>(&self, u: S, v: S) -> [S; 3] {
+        [v.cos() * u.sin(), v.sin() * u.sin(), u.cos()]
+    }
+}
+
+impl Geometry for Ball {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        crate::autodiff::evaluate(self, p)
+    }
+}
+
+impl DifferentiableGeometry for Ball {
+    fn du(&self) -> impl DifferentiableGeometry {
+        crate::autodiff::du(self)
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        crate::autodiff::dv(self)
+    }
+}
+
+#[test]
+fn gamma_of_a_surface_written_once() {
+    let p = Vec2::new(0.9, 0.2);
+    let metric = Ball.metric(&p);
+    assert!((metric - Sphere.metric(&p)).norm() < 1e-5);
+    let gamma = compute_gamma(&Ball, &p);
+    let expected = compute_gamma(&Sphere, &p);
+    for k in 0..2 {
+        for i in 0..2 {
+            for j in 0..2 {
+                assert!(close(gamma[k][i][j], expected[k][i][j]));
+            }
+        }
+    }
+    // Γ^u_vv = -sin u cos u
+    assert!(close(gamma[0][1][1], -p.x.sin() * p.x.cos()));
+    let [x, ..] = jets(&Ball, &p);
+    assert!(close(x.gradient.y, -p.y.sin() * p.x.sin()));
+}
@@ -0,0 +1,40 @@
+use nalgebra_glm::Vec2;
+
+use crate::bezier::fit;
+
+#[test]
+fn fit_splits_at_corners_and_keeps_lines_straight() {
+    let points = [
+        Vec2::new(0.0, 0.0),
+        Vec2::new(5.0, 0.0),
+        Vec2::new(10.0, 0.0),
+        Vec2::new(10.0, 10.0),
+    ];
+    let curves = fit(&points, 0.01);
+    assert_eq!(curves.len(), 2);
+    assert!(curves.iter().all(|c| c.is_straight(1e-6)));
+    assert_eq!(curves[0].p3, Vec2::new(10.0, 0.0));
+}
+
+#[test]
+fn fit_follows_smooth_runs() {
+    let points: Vec<Vec2> = (0..=100)
+        .map(|i| {
+            let x = i as f32 / 10.0;
+            Vec2::new(x, x.sin())
+        })
+        .collect();
+    let curves = fit(&points, 0.001);
+    assert!(curves.len() < 20, "{}", curves.len());
+    // consecutive curves meet and every point is close to some curve
+    for pair in curves.windows(2) {
+        assert_eq!(pair[0].p3, pair[1].p0);
+    }
+    for p in &points {
+        let distance = curves
+            .iter()
+            .flat_map(|c| (0..=1000).map(move |k| (c.point(k as f32 / 1000.0) - p).norm()))
+            .fold(f32::INFINITY, f32::min);
+        assert!(distance < 0.0015, "{distance}");
+    }
+}
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use nalgebra_glm::Vec2;
+
+use crate::{
+    calibration::Calibration, duration_extras::parse_duration, paper::Paper, polyline::Polyline2,
+    view_box::ViewBox,
+};
+
+fn square_paper(count: usize) -> Paper {
+    let mut paper = Paper::new(ViewBox::A5, 0.5);
+    for i in 0..count {
+        let (x, y) = (10.0 + 12.0 * i as f32, 20.0);
+        let mut square = Polyline2::new_closed();
+        for (dx, dy) in [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)] {
+            square.add(Vec2::new(x + dx, y + dy));
+        }
+        paper.add(square);
+    }
+    paper
+}
+
+#[test]
+fn round_trips_profiles() {
+    let mut calibration = Calibration::default();
+    let paper = square_paper(2);
+    calibration
+        .profile_mut("idraw")
+        .add_paper(&paper, 2000.0, 8000.0, Duration::from_secs(12));
+    calibration
+        .profile_mut("axidraw")
+        .add_paper(&paper, 1500.0, 6000.0, Duration::from_secs(20));
+
+    let mut text = Vec::new();
+    calibration.write(&mut text).unwrap();
+    let text = String::from_utf8(text).unwrap();
+    // two squares 12 mm apart with three right angles each, from the top
+    // left corner of the sheet
+    assert!(text.starts_with("axidraw:\nmeasurement 1500 6000 20 80 "), "{text}");
+    assert!(text.lines().nth(1).unwrap().ends_with(" 2 0 0 0 6 0 0"), "{text}");
+
+    let read = Calibration::read(&text).unwrap();
+    assert_eq!(read, calibration);
+    assert_eq!(read.profile("idraw").unwrap().plots[0].summary.pen_lifts, 2);
+    assert!(read.profile("plotter").is_none());
+}
+
+#[test]
+fn reports_errors_with_line_number() {
+    let err = Calibration::read("idraw:\nmeasurement 2000 8000 60 1 1 1 0 0 0 0 0 0\ndraw 0 0 1\n")
+        .unwrap_err();
+    assert!(err.to_string().contains("line 3"), "{err}");
+    let err =
+        Calibration::read("idraw:\nmeasurement 2000 8000 60 1 1 1.5 0 0 0 0 0 0\n").unwrap_err();
+    assert!(err.to_string().contains("line 2"), "{err}");
+    let err = Calibration::read("# no profile\nmeasurement 2000 8000 60 1 1 1 0 0 0 0 0 0\n")
+        .unwrap_err();
+    assert!(err.to_string().contains("line 2"), "{err}");
+    // one corner count for every angle bin
+    let err = Calibration::read("idraw:\nmeasurement 2000 8000 60 1 1 1 0\n").unwrap_err();
+    assert!(err.to_string().contains("6 corner counts"), "{err}");
+}
+
+#[test]
+fn fit_reports_residuals() {
+    let mut calibration = Calibration::default();
+    let profile = calibration.profile_mut("idraw");
+    assert_eq!(profile.fit().residuals.len(), 0);
+    for count in [1, 5, 20] {
+        let seconds = 3.0 + 1.5 * count as f32;
+        profile.add_paper(&square_paper(count), 2000.0, 8000.0, Duration::from_secs_f32(seconds));
+    }
+    let fit = profile.fit();
+    assert_eq!(fit.residuals.len(), 3);
+    assert!(fit.rms() < 1.0, "{:?}", fit.residuals);
+}
+
+#[test]
+fn parses_durations() {
+    assert_eq!(parse_duration("90"), Some(Duration::from_secs(90)));
+    assert_eq!(parse_duration("6:18"), Some(Duration::from_secs(378)));
+    assert_eq!(parse_duration("1:02:03"), Some(Duration::from_secs(3723)));
+    assert_eq!(parse_duration("six"), None);
+}
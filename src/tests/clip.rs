@@ -0,0 +1,112 @@
+use nalgebra_glm::{Vec2, Vec3};
+
+use crate::{
+    clip::{clip_to_polygons, clip_to_view_box, ClipMode},
+    paper::Paper,
+    polyline::{Polyline2, Polyline3},
+    view_box::ViewBox,
+};
+
+fn polyline(points: &[(f32, f32)]) -> Polyline2 {
+    points.iter().map(|&(x, y)| Vec2::new(x, y)).collect()
+}
+
+fn close(a: &Vec2, b: (f32, f32)) -> bool {
+    (a - Vec2::new(b.0, b.1)).norm() < 1e-4
+}
+
+#[test]
+fn view_box_splits_at_the_border() {
+    // leaves and re-enters the box
+    let line = polyline(&[
+        (-5.0, 5.0),
+        (5.0, 5.0),
+        (5.0, 15.0),
+        (8.0, 15.0),
+        (8.0, 5.0),
+    ]);
+    let pieces = clip_to_view_box(&line, ViewBox::new(0.0, 0.0, 10.0, 10.0));
+    assert_eq!(pieces.len(), 2);
+    assert_eq!(pieces[0].points.len(), 3);
+    assert!(close(&pieces[0].points[0], (0.0, 5.0)));
+    assert!(close(&pieces[0].points[2], (5.0, 10.0)));
+    assert!(close(&pieces[1].points[0], (8.0, 10.0)));
+    assert!(close(&pieces[1].points[1], (8.0, 5.0)));
+}
+
+#[test]
+fn extra_coordinates_are_interpolated() {
+    let line: Polyline3 =
+        [Vec3::new(-10.0, 0.0, 0.0), Vec3::new(10.0, 0.0, 1.0)].into_iter().collect();
+    let pieces = clip_to_view_box(&line, ViewBox::new(0.0, -1.0, 5.0, 2.0));
+    assert_eq!(pieces.len(), 1);
+    assert!((pieces[0].points[0].z - 0.5).abs() < 1e-6);
+    assert!((pieces[0].points[1].z - 0.75).abs() < 1e-6);
+}
+
+#[test]
+fn concave_polygon() {
+    // U shape, the horizontal line crosses both arms
+    let u = polyline(&[
+        (0.0, 0.0),
+        (3.0, 0.0),
+        (3.0, 2.0),
+        (2.0, 2.0),
+        (2.0, 1.0),
+        (1.0, 1.0),
+        (1.0, 2.0),
+        (0.0, 2.0),
+    ]);
+    let polygons = [u];
+    let line = polyline(&[(-1.0, 1.5), (4.0, 1.5)]);
+    let inside = clip_to_polygons(&line, &polygons, ClipMode::Inside);
+    assert_eq!(inside.len(), 2);
+    assert!(close(&inside[0].points[0], (0.0, 1.5)));
+    assert!(close(&inside[0].points[1], (1.0, 1.5)));
+    assert!(close(&inside[1].points[0], (2.0, 1.5)));
+    assert!(close(&inside[1].points[1], (3.0, 1.5)));
+
+    let outside = clip_to_polygons(&line, &polygons, ClipMode::Outside);
+    assert_eq!(outside.len(), 3);
+    let total: f32 = outside.iter().map(|p| (p.points[1] - p.points[0]).norm()).sum();
+    assert!((total - 3.0).abs() < 1e-4);
+}
+
+#[test]
+fn holes_use_even_odd() {
+    let outer = polyline(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]);
+    let hole = polyline(&[(4.0, 4.0), (6.0, 4.0), (6.0, 6.0), (4.0, 6.0)]);
+    let line = polyline(&[(-1.0, 5.0), (11.0, 5.0)]);
+    let pieces = clip_to_polygons(&line, &[outer, hole], ClipMode::Inside);
+    assert_eq!(pieces.len(), 2);
+    assert!(close(&pieces[0].points[1], (4.0, 5.0)));
+    assert!(close(&pieces[1].points[0], (6.0, 5.0)));
+}
+
+#[test]
+fn paper_clips_every_layer() {
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    paper.add(polyline(&[(-10.0, 10.0), (50.0, 10.0)]));
+    paper.add(polyline(&[(-10.0, -10.0), (-5.0, -5.0)]));
+    paper.clip(ViewBox::new(5.0, 5.0, 200.0, 287.0));
+    let polylines = paper.layers()[0].polylines();
+    assert_eq!(polylines.len(), 1);
+    assert!(close(&polylines[0].points[0], (5.0, 10.0)));
+}
+
+#[test]
+fn closed_polylines_stay_closed_unless_cut() {
+    let mut square = polyline(&[(1.0, 1.0), (4.0, 1.0), (4.0, 4.0), (1.0, 4.0)]);
+    square.closed = true;
+    let inside = clip_to_view_box(&square, ViewBox::new(0.0, 0.0, 10.0, 10.0));
+    assert_eq!(inside.len(), 1);
+    assert!(inside[0].closed);
+
+    // the right side is cut off, the rest is one open line around the corner
+    let cut = clip_to_view_box(&square, ViewBox::new(0.0, 0.0, 3.0, 10.0));
+    assert_eq!(cut.len(), 1);
+    assert!(!cut[0].closed);
+    assert!(close(&cut[0].points[0], (3.0, 4.0)));
+    assert!(close(cut[0].points.last().unwrap(), (3.0, 1.0)));
+    assert_eq!(cut[0].points.len(), 4);
+}
@@ -0,0 +1,78 @@
+use nalgebra_glm::Vec2;
+
+use crate::{
+    dash::{dash, dots},
+    polyline::Polyline2,
+};
+
+fn close(a: &Vec2, b: &Vec2) -> bool {
+    (a - b).norm() < 1e-4
+}
+
+#[test]
+fn dashes_follow_corners() {
+    // 10 mm along x, then 10 mm down
+    let path: Polyline2 = [
+        Vec2::new(0.0, 0.0),
+        Vec2::new(10.0, 0.0),
+        Vec2::new(10.0, 10.0),
+    ]
+    .into_iter()
+    .collect();
+    let dashes = dash(&path, &[4.0, 3.0], 0.0);
+    // dashes at 0-4, 7-11 and 14-18, the one over the corner keeps it
+    assert_eq!(dashes.len(), 3);
+    assert_eq!(dashes[0].points.len(), 2);
+    assert_eq!(dashes[1].points.len(), 3);
+    assert!(close(&dashes[1].points[0], &Vec2::new(7.0, 0.0)));
+    assert!(close(&dashes[1].points[1], &Vec2::new(10.0, 0.0)));
+    assert!(close(&dashes[1].points[2], &Vec2::new(10.0, 1.0)));
+    let drawn: f32 = dashes.iter().map(|d| d.length()).sum();
+    assert!((drawn - 12.0).abs() < 1e-4);
+
+    // the phase moves the pattern back, an odd pattern repeats with swapped roles
+    let shifted = dash(&path, &[4.0, 2.0], 3.0);
+    assert!(close(shifted[0].end().unwrap(), &Vec2::new(1.0, 0.0)));
+    let odd = dash(&path, &[3.0], 0.0);
+    assert!(close(&odd[1].points[0], &Vec2::new(6.0, 0.0)));
+
+    // invalid patterns leave the line solid
+    for pattern in [&[0.0, 0.0][..], &[-1.0, 2.0]] {
+        let solid = dash(&path, pattern, 0.0);
+        assert!(solid.len() == 1 && solid[0].points == path.points);
+    }
+}
+
+#[test]
+fn closed_polylines_join_over_the_start() {
+    let mut square = Polyline2::new_closed();
+    for (x, y) in [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)] {
+        square.add(Vec2::new(x, y));
+    }
+    // 40 mm around, a dash runs from 38 to 42
+    let dashes = dash(&square, &[4.0, 6.0], 2.0);
+    assert_eq!(dashes.len(), 4);
+    let joined = dashes.last().unwrap();
+    assert_eq!(joined.points.len(), 3);
+    assert!(close(&joined.points[0], &Vec2::new(0.0, 2.0)));
+    assert!(close(&joined.points[1], &Vec2::new(0.0, 0.0)));
+    assert!(close(&joined.points[2], &Vec2::new(2.0, 0.0)));
+
+    // no dot on the start twice
+    assert_eq!(dots(&square, 5.0, 0.0).len(), 8);
+}
+
+#[test]
+fn dots_are_evenly_spaced() {
+    let line: Polyline2 = [Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0)].into_iter().collect();
+    let points = dots(&line, 2.5, 0.0);
+    assert_eq!(points.len(), 5);
+    assert!(close(&points[4], &Vec2::new(10.0, 0.0)));
+    let points = dots(&line, 2.5, 1.0);
+    assert_eq!(points.len(), 4);
+    assert!(close(&points[0], &Vec2::new(1.5, 0.0)));
+    assert!(dots(&line, 0.0, 0.0).is_empty());
+    // too many to plot
+    assert!(dots(&line, 1e-8, 0.0).is_empty());
+    assert_eq!(dash(&line, &[1e-9, 1e-9], 0.0)[0].points, line.points);
+}
@@ -0,0 +1,92 @@
+use nalgebra_glm::{Vec2, Vec3};
+
+use crate::{
+    finite_difference::{check_derivatives, FiniteDifference},
+    geometries::{
+        gaussian::Gaussian, hole::Hole, plane::Plane, pulse::Pulse, sphere::Sphere, sum::Sum,
+        torus::Torus,
+    },
+    geometry::{acceleration, compute_gamma, DifferentiableGeometry, Geometry},
+};
+
+#[test]
+fn analytic_derivatives_agree_with_differences() {
+    let (min, max) = (Vec2::new(0.3, -2.0), Vec2::new(2.5, 2.0));
+    let assert_matches = |mismatches: Vec<_>| assert!(mismatches.is_empty(), "{mismatches:?}");
+    assert_matches(check_derivatives(&Plane, min, max, 6, 1e-2));
+    assert_matches(check_derivatives(&Sphere, min, max, 6, 1e-2));
+    assert_matches(check_derivatives(&Torus::new(0.5, 2.0), min, max, 6, 1e-2));
+    assert_matches(check_derivatives(&Gaussian, min, max, 6, 1e-2));
+    assert_matches(check_derivatives(&Hole, min, max, 6, 1e-2));
+}
+
+// z = u v with a typo in d/dv, like a wrong hand-written derivative
+struct Wrong {
+    u: usize,
+    v: usize,
+}
+
+impl Geometry for Wrong {
+    fn evaluate(&self, p: &Vec2) -> Vec3 {
+        match (self.u, self.v) {
+            (0, 0) => Vec3::new(p.x, p.y, p.x * p.y),
+            (1, 0) => Vec3::new(1.0, 0.0, p.y),
+            (0, 1) => Vec3::new(0.0, 1.0, 2.0 * p.x),
+            (1, 1) => Vec3::new(0.0, 0.0, 1.0),
+            _ => Vec3::zeros(),
+        }
+    }
+}
+
+impl DifferentiableGeometry for Wrong {
+    fn du(&self) -> impl DifferentiableGeometry {
+        Wrong { u: self.u + 1, v: self.v }
+    }
+    fn dv(&self) -> impl DifferentiableGeometry {
+        Wrong { u: self.u, v: self.v + 1 }
+    }
+}
+
+#[test]
+fn wrong_derivatives_are_caught() {
+    let mismatches = check_derivatives(
+        &Wrong { u: 0, v: 0 },
+        Vec2::new(-1.0, -1.0),
+        Vec2::new(1.0, 1.0),
+        3,
+        1e-2,
+    );
+    assert!(mismatches.iter().any(|m| m.derivative == "dv"));
+    assert!(mismatches.iter().all(|m| m.derivative != "du"));
+}
+
+#[test]
+fn geodesics_on_layered_heightmaps() {
+    let pulse = Pulse {
+        amplitude: 0.1,
+        sigma: 2.0,
+        c: 1.0,
+        lambda: 0.5,
+        cycles: 1.0,
+        t: 0.3,
+    };
+    let surface = FiniteDifference::new(Sum::new(Hole, pulse.clone()));
+    let p = Vec2::new(1.1, 0.4);
+
+    // matches the analytic hole where the pulse is negligible
+    let far = Vec2::new(6.0, 5.0);
+    let hole = compute_gamma(&Hole, &far);
+    let both = compute_gamma(
+        &FiniteDifference::new(Sum::new(Hole, Pulse { amplitude: 0.0, ..pulse })),
+        &far,
+    );
+    for k in 0..2 {
+        for i in 0..2 {
+            for j in 0..2 {
+                assert!((hole[k][i][j] - both[k][i][j]).abs() < 1e-3);
+            }
+        }
+    }
+    let a = acceleration(&surface, &p, &Vec2::new(0.0, 1.0));
+    assert!(a.x.is_finite() && a.y.is_finite() && a.norm() > 0.0);
+}
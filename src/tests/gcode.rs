@@ -0,0 +1,89 @@
+use nalgebra_glm::Vec2;
+
+use crate::{
+    gcode::{gcode_lines, GcodeOptions, PenLift},
+    paper::{Paper, Rgb},
+    polyline::Polyline2,
+    view_box::ViewBox,
+};
+
+fn paper_with_two_layers() -> Paper {
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    paper.add(Polyline2 {
+        points: vec![Vec2::new(10.0, 10.0), Vec2::new(20.0, 10.0)],
+        closed: false,
+    });
+    paper.add_layer("red", 0.3, Rgb::RED);
+    paper.add(Polyline2 {
+        points: vec![Vec2::new(10.0, 20.0), Vec2::new(10.0, 30.0)],
+        closed: false,
+    });
+    paper
+}
+
+#[test]
+fn servo_pen_lift_and_modal_feed() {
+    let paper = paper_with_two_layers();
+    let options = GcodeOptions::default();
+    let lines = gcode_lines(&paper, &options);
+
+    assert_eq!(lines.iter().filter(|line| line.starts_with("M3")).count(), 2);
+    assert_eq!(lines.iter().filter(|line| *line == "M0").count(), 1);
+    assert!(lines.contains(&"G1 X20.000 Y10.000 F2000".to_string()));
+    assert_eq!(lines.last().unwrap(), "M2");
+}
+
+#[test]
+fn z_pen_lift_with_flipped_y() {
+    let paper = paper_with_two_layers();
+    let options = GcodeOptions {
+        pen_lift: PenLift::Z { up: 5.0, down: 0.0, feed: 1000.0 },
+        flip_y: true,
+        pause_between_layers: false,
+        ..GcodeOptions::default()
+    };
+    let lines = gcode_lines(&paper, &options);
+
+    assert!(lines.contains(&"G1 Z0.000 F1000".to_string()));
+    assert!(lines.contains(&"G1 X10.000 Y287.000 F8000".to_string()));
+    assert!(!lines.contains(&"M0".to_string()));
+}
+
+#[test]
+fn closed_polylines_are_drawn_back_to_the_start() {
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    paper.add(Polyline2 {
+        points: vec![
+            Vec2::new(10.0, 10.0),
+            Vec2::new(20.0, 10.0),
+            Vec2::new(20.0, 20.0),
+        ],
+        closed: true,
+    });
+    let lines = gcode_lines(&paper, &GcodeOptions::default());
+    let visits = lines.iter().filter(|line| line.starts_with("G1 X10.000 Y10.000")).count();
+    // travel to the start and the closing segment
+    assert_eq!(visits, 2);
+}
+
+#[test]
+fn dots_hop_between_each_other() {
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    paper.dots([
+        Vec2::new(10.0, 10.0),
+        Vec2::new(11.0, 10.0),
+        Vec2::new(12.0, 10.0),
+    ]);
+    let options = GcodeOptions {
+        pen_lift: PenLift::Z { up: 5.0, down: 0.0, feed: 1000.0 },
+        ..GcodeOptions::default()
+    };
+    let lines = gcode_lines(&paper, &options);
+    let count = |line: &str| lines.iter().filter(|l| *l == line).count();
+    assert_eq!(count("G1 Z0.000 F1000"), 3);
+    assert_eq!(count("G1 Z1.000 F1000"), 2);
+    // fully up at the start and after the last dot
+    assert_eq!(count("G1 Z5.000 F1000"), 2);
+    // no drawing moves
+    assert!(!lines.iter().any(|l| l.ends_with("F2000")));
+}
@@ -0,0 +1,160 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use nalgebra_glm::Vec2;
+
+use crate::{
+    gcode::{GcodeOptions, PenLift},
+    grbl::{
+        driver::{Control, Driver, RX_BUFFER_SIZE},
+        simulator::{Simulator, State},
+    },
+    paper::{Paper, Rgb},
+    polyline::Polyline2,
+    view_box::ViewBox,
+};
+
+fn square_paper(n: usize) -> Paper {
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    for i in 0..n {
+        let o = 10.0 + i as f32;
+        paper.add(Polyline2 {
+            points: vec![
+                Vec2::new(o, o),
+                Vec2::new(o + 10.0, o),
+                Vec2::new(o + 10.0, o + 10.0),
+                Vec2::new(o, o + 10.0),
+            ],
+            closed: false,
+        });
+    }
+    paper
+}
+
+#[test]
+fn streams_paper_without_overflowing() {
+    let paper = square_paper(20);
+    let mut driver = Driver::new(Simulator::new());
+    let control = Control::new();
+    let mut updates = 0;
+    let progress = driver
+        .plot(&paper, &GcodeOptions::default(), &control, |_| updates += 1)
+        .unwrap();
+
+    assert_eq!(progress.acknowledged, progress.total);
+    assert_eq!(updates, progress.total);
+    let simulator = driver.into_inner();
+    assert!(!simulator.overflowed());
+    assert_eq!(simulator.lines(), progress.total);
+    let (drawing, _) = simulator.length();
+    assert!((drawing - 20.0 * 30.0).abs() < 1e-2);
+}
+
+#[test]
+fn waits_at_pen_changes_longer_than_the_timeout() {
+    // layers longer than the planner, so the driver has to wait for them
+    let mut paper = square_paper(6);
+    for (name, color) in [("red", Rgb::RED), ("blue", Rgb::BLUE)] {
+        paper.add_layer(name, 0.3, color);
+        paper.extend(square_paper(6).layers()[0].polylines().iter().cloned());
+    }
+
+    let mut driver = Driver::new(Simulator::new());
+    driver.timeout = Duration::from_millis(50);
+    driver.status_interval = Duration::from_millis(5);
+    let control = Control::new();
+    let started = Instant::now();
+    // the operator swaps pens and presses cycle start
+    let operator = control.clone();
+    let swaps = thread::spawn(move || {
+        for _ in 0..2 {
+            thread::sleep(Duration::from_millis(150));
+            operator.resume();
+        }
+    });
+    let progress = driver.plot(&paper, &GcodeOptions::default(), &control, |_| {}).unwrap();
+    swaps.join().unwrap();
+
+    assert_eq!(progress.acknowledged, progress.total);
+    assert!(started.elapsed() >= Duration::from_millis(300));
+    let simulator = driver.into_inner();
+    assert_eq!(simulator.lines(), progress.total);
+    let (drawing, _) = simulator.length();
+    assert!((drawing - 18.0 * 30.0).abs() < 1e-2);
+}
+
+#[test]
+fn simulator_holds_at_m0() {
+    use std::io::{Read, Write};
+    let mut simulator = Simulator::new();
+    simulator.write_all(b"M0\nG1 X10 F100\n").unwrap();
+    let mut output = String::new();
+    let mut buffer = [0; 256];
+    for _ in 0..4 {
+        let n = simulator.read(&mut buffer).unwrap();
+        output.push_str(&String::from_utf8_lossy(&buffer[..n]));
+    }
+    // both lines are acknowledged but the move waits for a cycle start
+    assert_eq!(output.matches("ok").count(), 2);
+    assert_eq!(simulator.state(), State::Hold);
+    assert_eq!(simulator.length().1, 0.0);
+    simulator.write_all(b"~").unwrap();
+    assert_eq!(simulator.state(), State::Idle);
+    assert!((simulator.length().1 - 10.0).abs() < 1e-4);
+}
+
+#[test]
+fn simulator_overflows_without_flow_control() {
+    use std::io::Write;
+    let mut simulator = Simulator::new();
+    for _ in 0..RX_BUFFER_SIZE {
+        simulator.write_all(b"G1 X1 Y1 F100\n").unwrap();
+    }
+    assert!(simulator.overflowed());
+}
+
+#[test]
+fn reports_errors_with_line_number() {
+    let lines = vec!["G21".to_string(), "G1 X1 Y1".to_string()];
+    let mut driver = Driver::new(Simulator::new());
+    let error = driver.stream(&lines, &Control::new(), |_| {}).unwrap_err();
+    assert!(error.to_string().contains("line 2"), "{error}");
+    assert!(error.to_string().contains("error:22"), "{error}");
+}
+
+#[test]
+fn pause_resume_and_cancel() {
+    let paper = square_paper(4);
+    let options = GcodeOptions {
+        pen_lift: PenLift::Z { up: 5.0, down: 0.0, feed: 1000.0 },
+        ..GcodeOptions::default()
+    };
+    let mut driver = Driver::new(Simulator::with_pen_lift(options.pen_lift));
+    let control = Control::new();
+    let callback_control = control.clone();
+    let mut paused_at = None;
+    driver
+        .plot(&paper, &options, &control, |progress| {
+            if progress.acknowledged == 3 {
+                callback_control.pause();
+                paused_at = Some(progress.sent);
+            } else if callback_control.is_paused() {
+                // no new lines are sent while paused
+                assert_eq!(Some(progress.sent), paused_at);
+                if progress.acknowledged == progress.sent {
+                    callback_control.resume();
+                }
+            }
+        })
+        .unwrap();
+    assert!(paused_at.is_some());
+    assert_eq!(driver.status().unwrap().split('|').next(), Some("<Idle"));
+
+    let cancelled = Control::new();
+    cancelled.cancel();
+    let error = driver.plot(&paper, &options, &cancelled, |_| {}).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::Interrupted);
+    assert_eq!(driver.into_inner().state(), State::Idle);
+}
@@ -0,0 +1,101 @@
+use nalgebra_glm::Vec2;
+
+use crate::{
+    hatch::{hatch, FillRule, HatchOptions},
+    paper::Paper,
+    polyline::Polyline2,
+    view_box::ViewBox,
+};
+
+// counter-clockwise square, or clockwise if `reverse`
+fn square(x: f32, y: f32, size: f32, reverse: bool) -> Polyline2 {
+    let mut points = vec![
+        Vec2::new(x, y),
+        Vec2::new(x + size, y),
+        Vec2::new(x + size, y + size),
+        Vec2::new(x, y + size),
+    ];
+    if reverse {
+        points.reverse();
+    }
+    Polyline2 { points, closed: true }
+}
+
+fn horizontal(spacing: f32, rule: FillRule) -> HatchOptions {
+    HatchOptions { angle: 0.0, spacing, rule, ..HatchOptions::default() }
+}
+
+fn total_length(lines: &[Polyline2]) -> f32 {
+    lines.iter().map(|l| l.length()).sum()
+}
+
+#[test]
+fn fills_a_square_with_parallel_lines() {
+    let lines = hatch(&[square(0.0, 0.0, 10.0, false)], &horizontal(1.0, FillRule::EvenOdd));
+    assert_eq!(lines.len(), 10);
+    for (k, line) in lines.iter().enumerate() {
+        assert_eq!(line.points.len(), 2);
+        assert!((line.points[0].y - (k as f32 + 0.5)).abs() < 1e-5);
+        assert!((line.length() - 10.0).abs() < 1e-4);
+    }
+
+    // any angle covers about area / spacing
+    let options = HatchOptions { angle: 0.3, spacing: 0.5, ..HatchOptions::default() };
+    let length = total_length(&hatch(&[square(0.0, 0.0, 10.0, false)], &options));
+    assert!((length - 200.0).abs() < 5.0, "{length}");
+}
+
+#[test]
+fn fill_rules_decide_holes() {
+    let outer = square(0.0, 0.0, 10.0, false);
+    let same = [outer.clone(), square(3.0, 3.0, 4.0, false)];
+    let opposite = [outer, square(3.0, 3.0, 4.0, true)];
+
+    let even_odd = hatch(&same, &horizontal(1.0, FillRule::EvenOdd));
+    assert_eq!(even_odd.len(), 6 + 4 * 2);
+    assert!((total_length(&even_odd) - 84.0).abs() < 1e-3);
+    // with the same winding the inner square does not cut a hole
+    let nonzero = hatch(&same, &horizontal(1.0, FillRule::NonZero));
+    assert_eq!(nonzero.len(), 10);
+    let nonzero = hatch(&opposite, &horizontal(1.0, FillRule::NonZero));
+    assert!((total_length(&nonzero) - 84.0).abs() < 1e-3);
+}
+
+#[test]
+fn cross_hatch_adds_a_second_direction() {
+    let options = HatchOptions { cross: true, ..horizontal(1.0, FillRule::EvenOdd) };
+    let lines = hatch(&[square(0.0, 0.0, 10.0, false)], &options);
+    assert_eq!(lines.len(), 20);
+    assert!((lines[15].points[0].x - lines[15].points[1].x).abs() < 1e-4);
+}
+
+#[test]
+fn zigzag_connects_lines_inside() {
+    let options = HatchOptions { zigzag: true, ..horizontal(1.0, FillRule::EvenOdd) };
+    let strokes = hatch(&[square(0.0, 0.0, 10.0, false)], &options);
+    assert_eq!(strokes.len(), 1);
+    assert_eq!(strokes[0].points.len(), 20);
+
+    // a hole splits the zig-zag, but only into a few strokes
+    let outlines = [square(0.0, 0.0, 10.0, false), square(3.0, 3.0, 4.0, false)];
+    let strokes = hatch(&outlines, &options);
+    assert!(strokes.len() <= 4, "{}", strokes.len());
+    for stroke in &strokes {
+        for segment in stroke.points.windows(2) {
+            let middle = 0.5 * (segment[0] + segment[1]);
+            let in_hole = middle.x > 3.0 && middle.x < 7.0 && middle.y > 3.0 && middle.y < 7.0;
+            assert!(!in_hole, "{segment:?}");
+        }
+    }
+}
+
+#[test]
+fn solid_fill_uses_the_pen_width() {
+    let mut paper = Paper::new(ViewBox::A6, 0.5);
+    paper.fill(&[square(10.0, 10.0, 20.0, false)]);
+    let polylines = paper.layers()[0].polylines();
+    assert_eq!(polylines.len(), 1);
+    // 45 degrees, about area / pen plus the steps between lines
+    let length = polylines[0].length();
+    assert!(length > 800.0 && length < 900.0, "{length}");
+}
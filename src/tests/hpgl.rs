@@ -0,0 +1,45 @@
+use nalgebra_glm::Vec2;
+
+use crate::{
+    hpgl::{hpgl_instructions, HpglOptions},
+    paper::{Paper, Rgb},
+    polyline::Polyline2,
+    view_box::ViewBox,
+};
+
+#[test]
+fn converts_millimetres_to_plotter_units() {
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    paper.add(Polyline2 {
+        points: vec![Vec2::new(10.0, 287.0), Vec2::new(20.0, 287.0)],
+        closed: false,
+    });
+    paper.add_layer("red", 0.3, Rgb::RED);
+    paper.add(Polyline2 {
+        points: vec![Vec2::new(0.0, 297.0), Vec2::new(1.0, 296.0)],
+        closed: false,
+    });
+
+    let options = HpglOptions {
+        pens: vec![3],
+        clip: Some(ViewBox::A4.pad(5.0)),
+        flip_y: true,
+    };
+    let instructions = hpgl_instructions(&paper, &options);
+
+    assert_eq!(
+        instructions,
+        vec![
+            "IN;",
+            "IW200,200,8200,11680;",
+            "SP3;",
+            "PU400,400;",
+            "PD800,400;",
+            "SP2;",
+            "PU0,0;",
+            "PD40,40;",
+            "PU;",
+            "SP0;",
+        ]
+    );
+}
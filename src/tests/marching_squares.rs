@@ -0,0 +1,16 @@
+use crate::{field::Field, marching_squares::find_contours, resolution::Resolution};
+
+#[test]
+fn contours_around_a_peak_are_closed() {
+    let resolution = Resolution::new(16, 16);
+    let values = (0..16 * 16)
+        .map(|i| {
+            let (x, y) = ((i % 16) as f32 - 7.5, (i / 16) as f32 - 7.5);
+            (x * x + y * y).sqrt()
+        })
+        .collect();
+    let contours = find_contours(&Field::from_buffer(resolution, &values), 5.0);
+    assert_eq!(contours.len(), 1);
+    assert!(contours[0].closed);
+    assert_ne!(contours[0].points.first(), contours[0].points.last());
+}
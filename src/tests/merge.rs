@@ -0,0 +1,84 @@
+use nalgebra_glm::Vec2;
+
+use crate::{merge::merge, paper::Paper, polyline::Polyline2, view_box::ViewBox};
+
+fn segment(a: (f32, f32), b: (f32, f32)) -> Polyline2 {
+    Polyline2 {
+        points: vec![Vec2::new(a.0, a.1), Vec2::new(b.0, b.1)],
+        closed: false,
+    }
+}
+
+#[test]
+fn segments_are_chained_and_reversed() {
+    // a path 0 -> 1 -> 2 -> 3 given out of order and partly backwards
+    let polylines = vec![
+        segment((2.0, 0.0), (1.0, 0.0)),
+        segment((0.0, 0.0), (1.0, 0.0)),
+        segment((3.0, 0.0), (2.0, 0.0)),
+    ];
+    let merged = merge(&polylines, 0.0);
+    assert_eq!(merged.len(), 1);
+    let xs: Vec<_> = merged[0].points.iter().map(|p| p.x).collect();
+    assert!(xs == [0.0, 1.0, 2.0, 3.0] || xs == [3.0, 2.0, 1.0, 0.0]);
+}
+
+#[test]
+fn tolerance_joins_nearly_touching_ends() {
+    let polylines = vec![
+        segment((0.0, 0.0), (1.0, 0.0)),
+        segment((1.005, 0.0), (2.0, 0.0)),
+    ];
+    assert_eq!(merge(&polylines, 0.001).len(), 2);
+    let merged = merge(&polylines, 0.01);
+    assert_eq!(merged.len(), 1);
+    // the tiny gap is still drawn
+    assert_eq!(merged[0].points.len(), 4);
+}
+
+#[test]
+fn branches_need_few_chains() {
+    // a star with four arms has four odd leaves, so two chains suffice
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    for end in [(1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0)] {
+        paper.add(segment((0.0, 0.0), end));
+    }
+    // a closed square becomes one loop
+    for (a, b) in [
+        ((5.0, 5.0), (6.0, 5.0)),
+        ((6.0, 5.0), (6.0, 6.0)),
+        ((5.0, 6.0), (6.0, 6.0)),
+    ] {
+        paper.add(segment(a, b));
+    }
+    paper.add(segment((5.0, 5.0), (5.0, 6.0)));
+
+    let report = paper.merge(1e-3);
+    assert_eq!((report.before, report.after), (8, 3));
+    let (closed, open): (Vec<_>, Vec<_>) =
+        paper.layers()[0].polylines().iter().partition(|polyline| polyline.closed);
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed[0].points.len(), 4);
+    // each chain runs through the center from one leaf to another
+    assert_eq!(open.len(), 2);
+    assert!(open.iter().all(|chain| chain.points.len() == 3));
+}
+
+#[test]
+fn loops_with_a_tail_are_one_chain() {
+    // a triangle whose ends nearly meet at the origin, with a tail from there
+    let triangle = Polyline2 {
+        points: vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(0.0, 1.0),
+            Vec2::new(0.0, 0.0005),
+        ],
+        closed: false,
+    };
+    let polylines = vec![segment((-1.0, 0.0), (0.0, 0.0)), triangle];
+    let merged = merge(&polylines, 1e-3);
+    assert_eq!(merged.len(), 1);
+    assert!(!merged[0].closed);
+    assert_eq!(merged[0].points.len(), 5);
+}
@@ -1,2 +1,26 @@
+mod algebra;
+mod autodiff;
+mod bezier;
+mod calibration;
+mod clip;
+mod dash;
 mod eq;
+mod finite_difference;
+mod gcode;
 mod geometries;
+mod grbl;
+mod hatch;
+mod hpgl;
+mod marching_squares;
+mod merge;
+mod offset;
+mod optimize;
+mod paper;
+mod polyline;
+mod preview;
+mod surfaces;
+mod svg_import;
+mod text;
+mod time_estimator;
+mod transformed;
+mod view_box;
@@ -0,0 +1,121 @@
+use std::f32::consts::PI;
+
+use nalgebra_glm::Vec2;
+
+use crate::{
+    offset::{contour_fill, insets, offset, Join},
+    polyline::Polyline2,
+};
+
+fn polygon(points: &[(f32, f32)]) -> Polyline2 {
+    Polyline2 {
+        points: points.iter().map(|&(x, y)| Vec2::new(x, y)).collect(),
+        closed: true,
+    }
+}
+
+fn square(x: f32, y: f32, size: f32) -> Polyline2 {
+    polygon(&[(x, y), (x + size, y), (x + size, y + size), (x, y + size)])
+}
+
+// holes have the opposite sign, so this is the enclosed area
+fn area(outlines: &[Polyline2]) -> f32 {
+    outlines
+        .iter()
+        .map(|outline| {
+            let p = &outline.points;
+            let n = p.len();
+            0.5 * (0..n)
+                .map(|i| p[i].x * p[(i + 1) % n].y - p[(i + 1) % n].x * p[i].y)
+                .sum::<f32>()
+        })
+        .sum::<f32>()
+        .abs()
+}
+
+#[test]
+fn joins_shape_the_corners() {
+    let outlines = [square(0.0, 0.0, 10.0)];
+    let miter = offset(&outlines, 1.0, Join::Miter(2.0));
+    assert_eq!(miter.len(), 1);
+    assert_eq!(miter[0].points.len(), 4);
+    assert!((area(&miter) - 144.0).abs() < 1e-3);
+    // a miter limit below sqrt(2) bevels right angles
+    assert!((area(&offset(&outlines, 1.0, Join::Miter(1.2))) - 142.0).abs() < 1e-3);
+    assert!((area(&offset(&outlines, 1.0, Join::Bevel)) - 142.0).abs() < 1e-3);
+    assert!((area(&offset(&outlines, 1.0, Join::Round)) - (140.0 + PI)).abs() < 0.05);
+}
+
+#[test]
+fn insets_shrink_outlines_and_holes_grow() {
+    let outlines = [square(0.0, 0.0, 10.0), square(3.0, 3.0, 4.0)];
+    let inset = offset(&outlines, -1.0, Join::Miter(2.0));
+    assert_eq!(inset.len(), 2);
+    assert!((area(&inset) - (64.0 - 36.0)).abs() < 1e-3);
+    // the hole swallows the rest
+    assert!(offset(&outlines, -1.6, Join::Miter(2.0)).is_empty());
+}
+
+#[test]
+fn self_intersections_split_and_merge() {
+    // two squares joined by a narrow bridge
+    let dumbbell = polygon(&[
+        (0.0, 0.0),
+        (10.0, 0.0),
+        (10.0, 4.0),
+        (14.0, 4.0),
+        (14.0, 0.0),
+        (24.0, 0.0),
+        (24.0, 10.0),
+        (14.0, 10.0),
+        (14.0, 6.0),
+        (10.0, 6.0),
+        (10.0, 10.0),
+        (0.0, 10.0),
+    ]);
+    assert_eq!(offset(std::slice::from_ref(&dumbbell), -0.5, Join::Miter(2.0)).len(), 1);
+    let split = offset(&[dumbbell], -1.5, Join::Miter(2.0));
+    assert_eq!(split.len(), 2);
+    assert!((area(&split) - 2.0 * 49.0).abs() < 1e-2);
+
+    let apart = [square(0.0, 0.0, 10.0), square(11.0, 0.0, 10.0)];
+    assert_eq!(offset(&apart, 1.0, Join::Round).len(), 1);
+
+    // a bow tie, the crossing is resolved first so both lobes are filled
+    let bow_tie = [polygon(&[
+        (0.0, 0.0),
+        (10.0, 10.0),
+        (10.0, 0.0),
+        (0.0, 10.0),
+    ])];
+    let same = offset(&bow_tie, 0.0, Join::Miter(2.0));
+    assert_eq!(same.len(), 2);
+    assert!((area(&same) - 50.0).abs() < 1e-3);
+    // the lobes grow into one, less than both grown on their own
+    let grown = offset(&bow_tie, 0.5, Join::Round);
+    assert_eq!(grown.len(), 1);
+    let apart = 2.0 * (25.0 + 0.5 * (10.0 + 10.0 * 2.0_f32.sqrt()) + PI * 0.25);
+    assert!(area(&grown) > 74.5 && area(&grown) < apart, "{}", area(&grown));
+    // each lobe shrinks towards the center of its inscribed circle
+    let shrunk = offset(&bow_tie, -0.5, Join::Miter(2.0));
+    assert_eq!(shrunk.len(), 2);
+    let inradius = 25.0 / (5.0 + 5.0 * 2.0_f32.sqrt());
+    let expected = 2.0 * 25.0 * (1.0 - 0.5 / inradius).powi(2);
+    assert!((area(&shrunk) - expected).abs() < 1e-2, "{}", area(&shrunk));
+}
+
+#[test]
+fn insets_run_until_the_shape_vanishes() {
+    let levels = insets(&[square(0.0, 0.0, 10.0)], 1.0, Join::Miter(2.0));
+    assert_eq!(levels.len(), 5);
+    assert!((area(&levels[4]) - 1.0).abs() < 1e-3);
+}
+
+#[test]
+fn contour_fill_is_one_stroke() {
+    let strokes = contour_fill(&[square(0.0, 0.0, 10.0)], 1.0, Join::Miter(2.0));
+    assert_eq!(strokes.len(), 1);
+    let length = strokes[0].length();
+    // rings of 9, 7, 5, 3 and 1 mm plus the steps between them
+    assert!(length > 100.0 && length < 110.0, "{length}");
+}
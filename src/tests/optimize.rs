@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use nalgebra_glm::Vec2;
+use rand::{Rng, SeedableRng};
+
+use crate::{
+    optimize::{optimize, OptimizeOptions},
+    paper::Paper,
+    polyline::Polyline2,
+    view_box::ViewBox,
+};
+
+fn segment(a: (f32, f32), b: (f32, f32)) -> Polyline2 {
+    Polyline2 {
+        points: vec![Vec2::new(a.0, a.1), Vec2::new(b.0, b.1)],
+        closed: false,
+    }
+}
+
+#[test]
+fn polylines_are_reversed_when_the_end_is_closer() {
+    let polylines = vec![
+        segment((0.0, 0.0), (10.0, 0.0)),
+        segment((20.0, 0.0), (11.0, 0.0)),
+    ];
+    let optimized = optimize(&polylines, Vec2::zeros(), &OptimizeOptions::default());
+    assert_eq!(optimized[1].points[0], Vec2::new(11.0, 0.0));
+
+    let options = OptimizeOptions { reverse: false, ..Default::default() };
+    let optimized = optimize(&polylines, Vec2::zeros(), &options);
+    assert_eq!(optimized[1].points[0], Vec2::new(20.0, 0.0));
+}
+
+#[test]
+fn many_short_segments() {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    for _ in 0..20000 {
+        let x = rng.gen_range(0.0..210.0);
+        let y = rng.gen_range(0.0..297.0);
+        paper.add(segment((x, y), (x + rng.gen_range(-2.0..2.0), y + 1.0)));
+    }
+    let (drawing, _) = paper.length();
+    let options = OptimizeOptions {
+        time_budget: Duration::from_millis(200),
+        ..Default::default()
+    };
+    let report = paper.optimize_with(&options);
+
+    assert_eq!(paper.layers()[0].polylines().len(), 20000);
+    assert!(report.travel_after < 0.1 * report.travel_before);
+    let (drawing_after, travel) = paper.length();
+    assert!((drawing_after - drawing).abs() < 1e-3 * drawing);
+    assert_eq!(travel, report.travel_after);
+}
+
+#[test]
+fn refinement_does_not_make_things_worse() {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+    let polylines: Vec<_> = (0..500)
+        .map(|_| {
+            let (x, y) = (rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0));
+            segment((x, y), (x + 3.0, y))
+        })
+        .collect();
+    let travel = |polylines: &[Polyline2]| {
+        let mut paper = Paper::new(ViewBox::A4, 0.5);
+        polylines.iter().for_each(|p| paper.add(p.clone()));
+        paper.length().1
+    };
+    let greedy = OptimizeOptions { time_budget: Duration::ZERO, ..Default::default() };
+    let greedy = travel(&optimize(&polylines, Vec2::zeros(), &greedy));
+    let refined = travel(&optimize(&polylines, Vec2::zeros(), &OptimizeOptions::default()));
+    assert!(refined < greedy);
+}
+
+#[test]
+fn loops_start_at_the_nearest_vertex() {
+    let square: Polyline2 = Polyline2 {
+        points: vec![
+            Vec2::new(10.0, 10.0),
+            Vec2::new(20.0, 10.0),
+            Vec2::new(20.0, 20.0),
+            Vec2::new(10.0, 20.0),
+        ],
+        closed: true,
+    };
+    let optimized = optimize(&[square], Vec2::new(21.0, 21.0), &OptimizeOptions::default());
+    assert!(optimized[0].closed);
+    assert_eq!(optimized[0].points[0], Vec2::new(20.0, 20.0));
+    assert_eq!(optimized[0].points[1], Vec2::new(10.0, 20.0));
+}
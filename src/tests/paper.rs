@@ -0,0 +1,179 @@
+use nalgebra_glm::Vec2;
+
+use crate::{
+    paper::{Mirror, Paper, Rgb, SvgFormat},
+    polyline::Polyline2,
+    svg_import::read_svg,
+    view_box::ViewBox,
+};
+
+fn segment(a: (f32, f32), b: (f32, f32)) -> Polyline2 {
+    Polyline2 {
+        points: vec![Vec2::new(a.0, a.1), Vec2::new(b.0, b.1)],
+        closed: false,
+    }
+}
+
+#[test]
+fn layers_are_measured_from_home() {
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    let home = paper.home();
+    paper.add(segment((0.0, 0.0), (10.0, 0.0)));
+    let red = paper.add_layer("red", 0.3, Rgb::RED);
+    paper.add(segment((0.0, 0.0), (0.0, 20.0)));
+
+    let first = paper.layers()[0].length(home);
+    let second = paper.layers()[red].length(home);
+    let (drawing, moving) = paper.length();
+    assert_eq!(paper.layers()[red].polylines().len(), 1);
+    assert_eq!(paper.pen(), 0.5);
+    assert!((drawing - first.0 - second.0).abs() < 1e-3);
+    assert!((moving - 2.0 * home.norm()).abs() < 1e-3);
+}
+
+#[test]
+fn save_writes_inkscape_layers() {
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    paper.add(segment((0.0, 0.0), (10.0, 0.0)));
+    paper.add_layer("red", 0.3, Rgb::RED);
+    paper.add(segment((0.0, 0.0), (0.0, 20.0)));
+
+    let path = std::env::temp_dir().join("plotter-layers.svg");
+    paper.save(path.to_str().unwrap()).unwrap();
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(content.matches("inkscape:groupmode=\"layer\"").count(), 2);
+    assert!(content.contains("inkscape:label=\"red\""));
+    assert!(content.contains("stroke=\"#ff0000\""));
+    assert!(content.contains("stroke-width=\"0.3\""));
+}
+
+#[test]
+fn closed_polylines_are_saved_as_polygons() {
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    paper.add(segment((0.0, 0.0), (10.0, 0.0)));
+    let mut triangle = segment((0.0, 0.0), (10.0, 0.0));
+    triangle.add(Vec2::new(0.0, 10.0));
+    triangle.closed = true;
+    paper.add(triangle);
+
+    let path = std::env::temp_dir().join("plotter-polygons.svg");
+    paper.save(path.to_str().unwrap()).unwrap();
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(content.matches("<polyline").count(), 1);
+    assert_eq!(content.matches("<polygon").count(), 1);
+}
+
+fn close(a: &Vec2, b: (f32, f32)) -> bool {
+    (a - Vec2::new(b.0, b.1)).norm() < 1e-4
+}
+
+#[test]
+fn bounds_cover_all_layers() {
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    assert_eq!(paper.bounds(), None);
+    paper.add(segment((1.0, 2.0), (3.0, 4.0)));
+    paper.add_layer("red", 0.3, Rgb::RED);
+    paper.add(segment((-1.0, 5.0), (0.0, 6.0)));
+    assert_eq!(paper.bounds(), Some(ViewBox::new(-1.0, 2.0, 4.0, 4.0)));
+}
+
+#[test]
+fn transformations() {
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    paper.add(segment((1.0, 0.0), (2.0, 0.0)));
+    paper.translate(Vec2::new(1.0, 1.0));
+    paper.rotate(std::f32::consts::FRAC_PI_2, Vec2::new(2.0, 1.0));
+    let points = &paper.layers()[0].polylines()[0].points;
+    assert!(close(&points[0], (2.0, 1.0)));
+    assert!(close(&points[1], (2.0, 2.0)));
+
+    paper.scale(2.0, Vec2::new(2.0, 1.0));
+    paper.mirror(Mirror::Vertical, Vec2::new(0.0, 0.0));
+    let points = &paper.layers()[0].polylines()[0].points;
+    assert!(close(&points[1], (2.0, -3.0)));
+    paper.mirror(Mirror::Horizontal, Vec2::new(1.0, 0.0));
+    assert!(close(&paper.layers()[0].polylines()[0].points[1], (0.0, -3.0)));
+}
+
+#[test]
+fn fit_keeps_aspect_and_centers() {
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    // 4 x 1 units in arbitrary coordinates
+    paper.add(segment((-2.0, 0.0), (2.0, 1.0)));
+    let area = ViewBox::A4.pad(10.0);
+    paper.fit(area);
+    let bounds = paper.bounds().unwrap();
+    assert!((bounds.width - area.width).abs() < 1e-3);
+    assert!((bounds.height - area.width / 4.0).abs() < 1e-3);
+    assert!((bounds.center() - area.center()).norm() < 1e-3);
+
+    paper.center(ViewBox::new(0.0, 0.0, 10.0, 10.0));
+    assert!((paper.bounds().unwrap().center() - Vec2::new(5.0, 5.0)).norm() < 1e-3);
+}
+
+fn written(paper: &Paper, format: SvgFormat) -> String {
+    let mut content = Vec::new();
+    paper.write(&mut content, format).unwrap();
+    String::from_utf8(content).unwrap()
+}
+
+#[test]
+fn paths_use_relative_commands_and_rounding() {
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    let mut square = Polyline2::new_closed();
+    for (x, y) in [(10.004, 10.0), (20.0, 10.0), (20.0, 20.5), (10.0, 20.5)] {
+        square.add(Vec2::new(x, y));
+    }
+    paper.add(square);
+    paper.add(segment((1.0, 2.0), (-3.25, 2.0)));
+
+    let content = written(&paper, SvgFormat::Paths { tolerance: 0.01, decimals: 2 });
+    assert!(content.contains("d=\"m10 10l10 0 0 10.5-10 0z\""), "{content}");
+    assert!(content.contains("d=\"m1 2l-4.25 0\""), "{content}");
+    assert!(!content.contains("<polyline"));
+}
+
+#[test]
+fn paths_fit_curves_within_tolerance() {
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    let circle: Vec<Vec2> = (0..500)
+        .map(|i| {
+            let angle = std::f32::consts::TAU * i as f32 / 500.0;
+            Vec2::new(100.0 + 50.0 * angle.cos(), 100.0 + 50.0 * angle.sin())
+        })
+        .collect();
+    paper.add(Polyline2 { points: circle, closed: true });
+
+    let polylines = written(&paper, SvgFormat::Polylines);
+    let paths = written(&paper, SvgFormat::Paths { tolerance: 0.05, decimals: 3 });
+    assert!(paths.len() * 10 < polylines.len(), "{} vs {}", paths.len(), polylines.len());
+
+    // read back, every point is close to the circle
+    let drawing = read_svg(&paths, 0.01).unwrap();
+    let points: Vec<_> = drawing.polylines().flat_map(|p| p.points.clone()).collect();
+    assert!(points.len() > 20);
+    for p in points {
+        let radius = (p - Vec2::new(100.0, 100.0)).norm();
+        assert!((radius - 50.0).abs() < 0.07, "{radius}");
+    }
+}
+
+#[test]
+fn runs_of_dots_share_one_path() {
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    paper.dots([Vec2::new(1.0, 2.0), Vec2::new(3.5, 2.0)]);
+    paper.add(segment((0.0, 0.0), (1.0, 0.0)));
+    paper.dot(Vec2::new(0.1234, 0.0));
+
+    let content = written(&paper, SvgFormat::Polylines);
+    assert_eq!(content.matches("stroke-linecap=\"round\"").count(), 2);
+    assert!(content.contains("d=\"m1 2l0 0m2.5 0l0 0\""), "{content}");
+    assert!(content.contains("d=\"m.123 0l0 0\""), "{content}");
+    // the line stays between the runs
+    let line = content.find("<polyline").unwrap();
+    assert!(content.find("m1 2").unwrap() < line && line < content.find("m.123").unwrap());
+}
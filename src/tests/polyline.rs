@@ -0,0 +1,89 @@
+use nalgebra_glm::{Vec2, Vec3};
+
+use crate::polyline::{Polyline2, Polyline3};
+
+fn polyline(points: &[(f32, f32)]) -> Polyline2 {
+    points.iter().map(|&(x, y)| Vec2::new(x, y)).collect()
+}
+
+fn arc_length(polyline: &Polyline2) -> f32 {
+    polyline.points.windows(2).map(|w| (w[1] - w[0]).norm()).sum()
+}
+
+#[test]
+fn simplify_drops_collinear_points() {
+    let line: Polyline2 = (0..=256).map(|i| Vec2::new(i as f32, 0.001 * (i % 2) as f32)).collect();
+    let simplified = line.simplify(0.01);
+    assert_eq!(simplified.points.len(), 2);
+    assert_eq!(line.simplify_area(0.01).points.len(), 2);
+
+    let corner = polyline(&[(0.0, 0.0), (1.0, 0.01), (2.0, 0.0), (2.0, 2.0)]);
+    let simplified = corner.simplify(0.1);
+    assert_eq!(simplified.points.len(), 3);
+    assert_eq!(simplified.points[1], Vec2::new(2.0, 0.0));
+    assert_eq!(corner.simplify_area(0.1).points, simplified.points);
+}
+
+#[test]
+fn simplify_works_in_3d() {
+    let bent: Polyline3 = [
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(2.0, 0.0, 1.0),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(bent.simplify(0.01).points.len(), 3);
+    assert_eq!(bent.simplify_area(0.01).points.len(), 3);
+}
+
+#[test]
+fn resample_uses_equal_spacing() {
+    let l = polyline(&[(0.0, 0.0), (3.0, 0.0), (3.0, 7.0)]);
+    let resampled = l.resample(1.0);
+    assert_eq!(resampled.points.len(), 11);
+    assert!((resampled.points[2] - Vec2::new(2.0, 0.0)).norm() < 1e-5);
+    assert_eq!(resampled.points.last(), l.points.last());
+    assert!((resampled.points[5] - Vec2::new(3.0, 2.0)).norm() < 1e-5);
+    for spacing in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+        assert_eq!(l.resample(spacing).points, l.points);
+    }
+}
+
+#[test]
+fn tiny_spacings_are_capped() {
+    let l = polyline(&[(0.0, 0.0), (1000.0, 0.0)]);
+    assert!(l.resample(1e-9).points.len() <= (1 << 20) + 1);
+    assert!(l.subdivide(1e-9).points.len() <= (1 << 20) + 1);
+}
+
+#[test]
+fn subdivide_limits_segment_length() {
+    let l = polyline(&[(0.0, 0.0), (10.0, 0.0), (10.0, 0.5)]);
+    let subdivided = l.subdivide(3.0);
+    assert_eq!(subdivided.points.len(), 6);
+    assert!(subdivided.points.windows(2).all(|w| (w[1] - w[0]).norm() <= 3.0));
+    assert!((arc_length(&subdivided) - arc_length(&l)).abs() < 1e-4);
+    // lengths that cannot be reached leave the polyline as it is
+    for max_length in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+        assert_eq!(l.subdivide(max_length).points, l.points);
+    }
+}
+
+#[test]
+fn only_closed_polylines_return_to_the_start() {
+    let mut l = polyline(&[(0.0, 0.0), (3.0, 0.0), (3.0, 4.0)]);
+    assert_eq!(l.length(), 7.0);
+    assert_eq!(l.end(), Some(&Vec2::new(3.0, 4.0)));
+    l.closed = true;
+    assert_eq!(l.length(), 12.0);
+    assert_eq!(l.end(), Some(&Vec2::new(0.0, 0.0)));
+    assert_eq!(l.points_drawn().count(), 4);
+
+    // processing keeps the loop closed without repeating the first point
+    let subdivided = l.subdivide(1.0);
+    assert!(subdivided.closed);
+    assert_eq!(subdivided.points.len(), 12);
+    assert!((subdivided.length() - 12.0).abs() < 1e-4);
+}
@@ -0,0 +1,58 @@
+use nalgebra_glm::Vec2;
+
+use crate::{
+    paper::{Paper, Rgb},
+    polyline::Polyline2,
+    preview::{render_preview, PreviewOptions},
+    view_box::ViewBox,
+};
+
+fn segment(a: (f32, f32), b: (f32, f32)) -> Polyline2 {
+    [Vec2::new(a.0, a.1), Vec2::new(b.0, b.1)].into_iter().collect()
+}
+
+// pixel color at a position in millimetres
+fn pixel(pixmap: &tiny_skia::Pixmap, dpi: f32, x: f32, y: f32) -> (u8, u8, u8) {
+    let scale = dpi / 25.4;
+    let p = pixmap.pixel((x * scale) as u32, (y * scale) as u32).unwrap();
+    (p.red(), p.green(), p.blue())
+}
+
+#[test]
+fn strokes_and_travel() {
+    let mut paper = Paper::new(ViewBox::A6, 1.0);
+    paper.add(segment((10.0, 10.0), (90.0, 10.0)));
+    paper.add_layer("red", 2.0, Rgb::RED);
+    paper.add(segment((10.0, 100.0), (90.0, 100.0)));
+
+    let options = PreviewOptions { dpi: 100.0, numbers: true, ..Default::default() };
+    let pixmap = render_preview(&paper, &options).unwrap();
+    assert_eq!((pixmap.width(), pixmap.height()), (413, 583));
+
+    assert_eq!(pixel(&pixmap, 100.0, 50.0, 10.0), (0, 0, 0));
+    // the red pen is 2 mm wide
+    assert_eq!(pixel(&pixmap, 100.0, 50.0, 100.8), (255, 0, 0));
+    assert_eq!(pixel(&pixmap, 100.0, 50.0, 50.0), (255, 255, 255));
+    // travel from home (0, 105) to the start of the first line
+    let (r, g, b) = pixel(&pixmap, 100.0, 5.0, 57.5);
+    assert!(r > 200 && g < 150 && b > 100);
+}
+
+#[test]
+fn travel_can_be_hidden() {
+    let mut paper = Paper::new(ViewBox::A6, 1.0);
+    paper.add(segment((10.0, 10.0), (90.0, 10.0)));
+    let options = PreviewOptions { dpi: 100.0, travel: false, ..Default::default() };
+    let pixmap = render_preview(&paper, &options).unwrap();
+    assert_eq!(pixel(&pixmap, 100.0, 5.0, 57.5), (255, 255, 255));
+}
+
+#[test]
+fn dots_are_drawn() {
+    let mut paper = Paper::new(ViewBox::A6, 2.0);
+    paper.dot(Vec2::new(50.0, 50.0));
+    let options = PreviewOptions { dpi: 100.0, travel: false, ..Default::default() };
+    let pixmap = render_preview(&paper, &options).unwrap();
+    assert_eq!(pixel(&pixmap, 100.0, 50.5, 50.0), (0, 0, 0));
+    assert_eq!(pixel(&pixmap, 100.0, 52.0, 50.0), (255, 255, 255));
+}
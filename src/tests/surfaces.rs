@@ -0,0 +1,126 @@
+use nalgebra_glm::{Vec2, Vec3};
+
+use crate::{
+    eq::NewtonRaphsonOptions,
+    finite_difference::check_derivatives,
+    geometries::{
+        closest_point::ClosestPoint, minimal::*, non_orientable::*, pseudospherical::*,
+        quadrics::*, saddles::*, superquadric::*,
+    },
+    geometry::{DifferentiableGeometry, Geometry},
+    raytracer::{Ray, Tracer},
+    sdf::SDF,
+};
+
+// The level is zero on the surface and changes sign across it
+fn assert_occludes(surface: &(impl DifferentiableGeometry + SDF), uvs: &[Vec2]) {
+    for uv in uvs {
+        let point = surface.evaluate(uv);
+        let normal = surface.du().evaluate(uv).cross(&surface.dv().evaluate(uv)).normalize();
+        let level = surface.sdf(&point);
+        assert!(level.abs() < 2e-3, "{level} at {uv}");
+        let (front, back) =
+            (surface.sdf(&(point + normal * 0.05)), surface.sdf(&(point - normal * 0.05)));
+        assert!(front * back < 0.0, "{front} and {back} at {uv}");
+    }
+}
+
+#[test]
+fn implicit_functions_occlude() {
+    let uvs = [
+        Vec2::new(0.3, 0.4),
+        Vec2::new(-0.7, 1.1),
+        Vec2::new(1.2, -0.5),
+    ];
+    assert_occludes(&Ellipsoid::new(1.0, 2.0, 3.0), &uvs);
+    assert_occludes(&HyperboloidOneSheet::new(1.0, 1.5, 0.5), &uvs);
+    assert_occludes(&HyperboloidTwoSheets::new(1.0, 1.5, 0.5), &uvs);
+    assert_occludes(&HyperbolicParaboloid::new(1.0, 2.0), &uvs);
+    assert_occludes(&MonkeySaddle, &uvs);
+    assert_occludes(&Catenoid::new(0.5), &uvs);
+    assert_occludes(&Helicoid::new(0.5), &uvs);
+    assert_occludes(&Superquadric::new(1.0, 2.0, 1.5, 0.7, 1.3), &uvs);
+    // away from the rim at u = 0, where the two halves meet
+    let uvs = [
+        Vec2::new(0.8, 0.4),
+        Vec2::new(2.0, 1.1),
+        Vec2::new(-1.2, -2.5),
+    ];
+    assert_occludes(&Pseudosphere::new(2.0), &uvs);
+}
+
+// The shell around the surface is thinner than the offsets along the normal,
+// so the level is negative on the surface and positive to either side
+fn assert_shell_occludes<G: DifferentiableGeometry>(surface: &ClosestPoint<G>, uvs: &[Vec2]) {
+    for uv in uvs {
+        let point = surface.evaluate(uv);
+        let normal = surface.du().evaluate(uv).cross(&surface.dv().evaluate(uv)).normalize();
+        let level = surface.sdf(&point);
+        assert!((level + surface.thickness).abs() < 1e-4, "{level} at {uv}");
+        let (front, back) =
+            (surface.sdf(&(point + normal * 0.05)), surface.sdf(&(point - normal * 0.05)));
+        assert!(front > 0.0 && back > 0.0, "{front} and {back} at {uv}");
+    }
+}
+
+#[test]
+fn closest_points_occlude_surfaces_without_an_inside() {
+    let uvs = [
+        Vec2::new(1.0, 0.2),
+        Vec2::new(4.0, -0.3),
+        Vec2::new(5.5, 0.1),
+    ];
+    assert_shell_occludes(&Enneper.occluder(), &[Vec2::new(0.3, 0.4), Vec2::new(-0.7, 1.1)]);
+    assert_shell_occludes(&MoebiusStrip::new(2.0, 0.5).occluder(), &uvs);
+    let uvs = [
+        Vec2::new(1.0, 0.5),
+        Vec2::new(4.0, 2.5),
+        Vec2::new(2.5, 1.2),
+    ];
+    assert_shell_occludes(&KleinBottle::new(2.0).occluder(), &uvs);
+    // away from the cusp of the tractrix at v = π/2
+    let uvs = [
+        Vec2::new(1.0, 0.5),
+        Vec2::new(4.0, 0.8),
+        Vec2::new(7.0, 0.6),
+    ];
+    assert_shell_occludes(&Dini::new(1.0, 0.2).occluder(), &uvs);
+    let uvs = [
+        Vec2::new(0.3, 0.5),
+        Vec2::new(-0.6, 2.5),
+        Vec2::new(1.2, 1.2),
+    ];
+    assert_shell_occludes(&BoySurface.occluder(), &uvs);
+}
+
+#[test]
+fn automatic_derivatives_match_finite_differences() {
+    let (min, max) = (Vec2::new(0.2, 0.3), Vec2::new(1.2, 1.3));
+    for mismatches in [
+        check_derivatives(&KleinBottle::new(2.0), min, max, 4, 1e-2),
+        check_derivatives(&BoySurface, min, max, 4, 1e-2),
+        check_derivatives(&Dini::new(1.0, 0.2), min, max, 4, 1e-2),
+        check_derivatives(&Superquadric::new(1.0, 2.0, 1.5, 0.7, 1.3), min, max, 4, 1e-2),
+        check_derivatives(&MonkeySaddle, min, max, 4, 1e-2),
+    ] {
+        assert!(mismatches.is_empty(), "{mismatches:?}");
+    }
+}
+
+#[test]
+fn closest_point_shells_are_hit_along_rays() {
+    let strip = MoebiusStrip::new(2.0, 0.5).occluder();
+    // the steps `reproject` takes are much longer than the shell is thick
+    let tracer = Tracer {
+        near: 0.1,
+        far: 4.0,
+        steps: 200,
+        newton_raphson: NewtonRaphsonOptions::default(),
+    };
+    // the ray at z = 1.5 passes above the strip, the one at z = 0.25 crosses
+    // it where it stands upright at u = π
+    let along_x = |z| Ray::new(Vec3::new(-4.0, 0.0, z), Vec3::new(1.0, 0.0, 0.0));
+    assert!(tracer.trace(&along_x(1.5), &strip).is_none());
+    let hit = tracer.trace(&along_x(0.25), &strip).unwrap();
+    assert!((hit - Vec3::new(-2.0, 0.0, 0.25)).norm() < 2e-3, "{hit}");
+}
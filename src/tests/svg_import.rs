@@ -0,0 +1,109 @@
+use nalgebra_glm::Vec2;
+
+use crate::{
+    paper::{Paper, Rgb},
+    polyline::Polyline2,
+    svg_import::read_svg,
+    view_box::ViewBox,
+};
+
+fn close_to(a: &Vec2, b: (f32, f32)) -> bool {
+    (a - Vec2::new(b.0, b.1)).norm() < 1e-3
+}
+
+#[test]
+fn reads_basic_shapes_with_transforms() {
+    let content = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 50" width="100mm">
+        <g transform="translate(10 20)">
+            <line x1="0" y1="0" x2="5" y2="0"/>
+            <polygon points="0,0 1,0 1,1"/>
+            <g transform="scale(2)"><rect x="1" y="1" width="2" height="3"/></g>
+        </g>
+        <defs><circle r="4"/></defs>
+        <polyline points="1 2 3 4 5 6"/>
+    </svg>"#;
+    let drawing = read_svg(content, 0.01).unwrap();
+    assert_eq!(drawing.view_box, ViewBox::new(0.0, 0.0, 100.0, 50.0));
+    let polylines: Vec<_> = drawing.polylines().collect();
+    assert_eq!(polylines.len(), 4);
+    assert!(close_to(&polylines[0].points[1], (15.0, 20.0)));
+    assert!(polylines[1].closed);
+    assert_eq!(polylines[1].points.len(), 3);
+    assert!(polylines[2].closed && !polylines[3].closed);
+    assert!(close_to(&polylines[2].points[0], (12.0, 22.0)));
+    assert!(close_to(&polylines[2].points[2], (16.0, 28.0)));
+    assert_eq!(polylines[3].points.len(), 3);
+}
+
+#[test]
+fn reads_compact_number_lists() {
+    // a second decimal point or one after an exponent starts a new number
+    let content = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 50">
+        <polyline points="1e1.5 .5.5 -1e-1.25"/>
+    </svg>"#;
+    let drawing = read_svg(content, 0.01).unwrap();
+    let polylines: Vec<_> = drawing.polylines().collect();
+    let points = &polylines[0].points;
+    assert_eq!(points.len(), 3);
+    assert!(close_to(&points[0], (10.0, 0.5)));
+    assert!(close_to(&points[1], (0.5, 0.5)));
+    assert!(close_to(&points[2], (-0.1, 0.25)));
+}
+
+#[test]
+fn flattens_curves_within_tolerance() {
+    let content = r#"<svg viewBox="0 0 100 100">
+        <path d="M 0 0 C 0 10 10 10 10 0 Q 15 -5 20 0 A 5 5 0 0 1 30 0 z"/>
+        <circle cx="50" cy="50" r="10"/>
+    </svg>"#;
+    let tolerance = 0.05;
+    let drawing = read_svg(content, tolerance).unwrap();
+    let polylines: Vec<_> = drawing.polylines().collect();
+    assert_eq!(polylines.len(), 2);
+
+    let path = polylines[0];
+    assert!(path.points.len() > 10);
+    assert!(path.points.iter().any(|p| close_to(p, (10.0, 0.0))));
+    assert!(path.points.iter().any(|p| close_to(p, (20.0, 0.0))));
+    // positive sweep runs through increasing angles, so up to y = -5
+    assert!(path.points.iter().any(|p| (p.y + 5.0).abs() < tolerance));
+    // closing the path does not repeat the first point
+    assert!(path.closed);
+    assert!(close_to(path.points.last().unwrap(), (30.0, 0.0)));
+
+    let circle = polylines[1];
+    for point in &circle.points {
+        assert!(((point - Vec2::new(50.0, 50.0)).norm() - 10.0).abs() < 1e-3);
+    }
+    let drawn: Vec<_> = circle.points_drawn().copied().collect();
+    for pair in drawn.windows(2) {
+        let middle = (pair[0] + pair[1]) * 0.5;
+        assert!(10.0 - (middle - Vec2::new(50.0, 50.0)).norm() <= tolerance);
+    }
+}
+
+#[test]
+fn round_trips_paper_layers() {
+    let mut paper = Paper::new(ViewBox::A4, 0.5);
+    paper.add(Polyline2 {
+        points: vec![Vec2::new(1.0, 2.0), Vec2::new(3.0, 4.0)],
+        closed: false,
+    });
+    paper.add_layer("red", 0.3, Rgb::RED);
+    paper.add(Polyline2 {
+        points: vec![Vec2::new(5.0, 6.0), Vec2::new(7.0, 8.0)],
+        closed: false,
+    });
+    let path = std::env::temp_dir().join("plotter-roundtrip.svg");
+    paper.save(path.to_str().unwrap()).unwrap();
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let loaded = read_svg(&content, 0.01).unwrap().into_paper(0.5);
+    assert_eq!(loaded.view_box, ViewBox::A4);
+    assert_eq!(loaded.layers().len(), 2);
+    assert_eq!(loaded.layers()[1].name, "red");
+    assert_eq!(loaded.layers()[1].color, Rgb::RED);
+    assert_eq!(loaded.layers()[1].pen, 0.3);
+    assert_eq!(loaded.length(), paper.length());
+}
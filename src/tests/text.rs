@@ -0,0 +1,104 @@
+use nalgebra_glm::Vec2;
+
+use crate::{
+    paper::Paper,
+    polyline::Polyline2,
+    text::{Align, Font, TextOptions},
+    view_box::ViewBox,
+};
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-3
+}
+
+fn bounds(polylines: &[Polyline2]) -> ViewBox {
+    ViewBox::bounding(polylines.iter().flat_map(|p| &p.points)).unwrap()
+}
+
+#[test]
+fn simplex_has_printable_ascii() {
+    let font = Font::simplex();
+    // 21 units from the top of capitals to the baseline
+    let options = TextOptions { size: 21.0, ..TextOptions::default() };
+    assert!(close(font.width("A", &options), 18.0));
+    assert!(close(font.width("~", &options), 24.0));
+    // characters outside the font are skipped
+    assert!(close(font.width("Aé\u{1}", &options), 18.0));
+
+    let a = font.layout("A", Vec2::new(0.0, 0.0), &options);
+    assert_eq!(a.len(), 3);
+    let b = bounds(&a);
+    assert!(close(b.y, -21.0) && close(b.bottom(), 0.0));
+}
+
+#[test]
+fn size_spacing_and_alignment() {
+    let font = Font::simplex();
+    let options = TextOptions { size: 10.0, ..TextOptions::default() };
+    let width = font.width("plot", &options);
+    let spaced = TextOptions { letter_spacing: 1.0, ..options };
+    assert!(close(font.width("plot", &spaced), width + 3.0));
+
+    let x = 100.0;
+    for (align, expected) in [
+        (Align::Left, x),
+        (Align::Center, x - width / 2.0),
+        (Align::Right, x - width),
+    ] {
+        let options = TextOptions { align, ..options };
+        let left = bounds(&font.layout("plot", Vec2::new(x, 0.0), &options)).x;
+        let start = bounds(&font.layout(
+            "plot",
+            Vec2::new(expected, 0.0),
+            &TextOptions { align: Align::Left, ..options },
+        ))
+        .x;
+        assert!(close(left, start));
+    }
+}
+
+#[test]
+fn lines_stack_downwards() {
+    let font = Font::simplex();
+    let options = TextOptions { size: 10.0, line_spacing: 2.0, ..TextOptions::default() };
+    let text = font.layout("I\nI", Vec2::new(0.0, 50.0), &options);
+    assert_eq!(text.len(), 2);
+    assert!(close(text[0].points[1].y, 50.0));
+    assert!(close(text[1].points[1].y, 70.0));
+}
+
+#[test]
+fn along_a_straight_path_matches_layout() {
+    let font = Font::simplex();
+    let options = TextOptions { size: 7.0, ..TextOptions::default() };
+    let path: Polyline2 = [Vec2::new(10.0, 30.0), Vec2::new(200.0, 30.0)].into_iter().collect();
+    let along = font.along("Hershey", &path, &options);
+    let flat = font.layout("Hershey", Vec2::new(10.0, 30.0), &options);
+    assert_eq!(along.len(), flat.len());
+    for (a, b) in along.iter().zip(&flat) {
+        for (p, q) in a.points.iter().zip(&b.points) {
+            assert!((p - q).norm() < 1e-3);
+        }
+    }
+
+    // going up the page the letters lie on their side
+    let up: Polyline2 = [Vec2::new(0.0, 100.0), Vec2::new(0.0, 0.0)].into_iter().collect();
+    let b = bounds(&font.along("II", &up, &options));
+    assert!(close(b.width, 7.0) && b.height < 7.0);
+    // on the left of the path, which is the top of the letters
+    assert!(close(b.right(), 0.0));
+}
+
+#[test]
+fn parses_continued_lines_and_adds_to_paper() {
+    // a box glyph for the space character split over two lines
+    let font = Font::parse("   32  6JZ\nJFZFZ[J[JF\n").unwrap();
+    let mut paper = Paper::new(ViewBox::A6, 0.3);
+    paper.extend(font.layout(" ", Vec2::new(0.0, 0.0), &TextOptions::default()));
+    assert_eq!(paper.layers()[0].polylines()[0].points.len(), 5);
+
+    let Err(err) = Font::parse("   32  6JZJF\n") else {
+        panic!("parsed a glyph that is cut short");
+    };
+    assert!(err.to_string().contains("line 1"), "{err}");
+}
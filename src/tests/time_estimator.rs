@@ -0,0 +1,158 @@
+use nalgebra_glm::Vec2;
+
+use crate::time_estimator::{fit_to, Estimator, Measurement, Move, Summary, Toolpath};
+
+// a zigzag of `n` segments covering `length` mm in total
+fn zigzag(n: usize, length: f32) -> Toolpath {
+    let step = length / n as f32 / 2.0_f32.sqrt();
+    let points = (0..=n).map(|i| Vec2::new(i as f32 * step, (i % 2) as f32 * step)).collect();
+    Toolpath { moves: vec![Move { points, pen_down: true }] }
+}
+
+fn strokes(count: usize, length: f32) -> Toolpath {
+    let moves = (0..count)
+        .flat_map(|i| {
+            let y = i as f32;
+            [
+                Move {
+                    points: vec![Vec2::new(0.0, y), Vec2::new(length, y)],
+                    pen_down: true,
+                },
+                Move {
+                    points: vec![Vec2::new(length, y), Vec2::new(0.0, y + 1.0)],
+                    pen_down: false,
+                },
+            ]
+        })
+        .collect();
+    Toolpath { moves }
+}
+
+#[test]
+fn tiny_segments_are_slower_than_one_line() {
+    let estimator = Estimator::default();
+    let line = estimator.estimate_time(1000.0, 0.0, 2000.0, 8000.0);
+    let tiny = estimator.estimate_toolpath(&zigzag(10_000, 1000.0), 2000.0, 8000.0);
+    assert!(tiny > line * 5, "{tiny:?} vs {line:?}");
+    // a long line is close to length / speed
+    let expected = 1000.0 / (2000.0 / 60.0) + estimator.pen_lift;
+    assert!((line.as_secs_f32() - expected).abs() < 1.0);
+}
+
+#[test]
+fn pen_lifts_cost_time() {
+    let estimator = Estimator { pen_lift: 0.5, ..Estimator::default() };
+    let free = Estimator { pen_lift: 0.0, ..estimator };
+    let path = strokes(10, 50.0);
+    assert_eq!(path.pen_lifts(), 10);
+    let difference = estimator.estimate_toolpath(&path, 2000.0, 8000.0)
+        - free.estimate_toolpath(&path, 2000.0, 8000.0);
+    assert!((difference.as_secs_f32() - 5.0).abs() < 1e-3);
+}
+
+#[test]
+fn summaries_rebuild_equivalent_toolpaths() {
+    let summary = Summary {
+        draw_length: 900.0,
+        move_length: 300.0,
+        pen_lifts: 4,
+        corners: [3, 0, 2, 10, 1, 5],
+    };
+    let rebuilt = Toolpath::from_summary(&summary).summary();
+    assert_eq!((rebuilt.pen_lifts, rebuilt.corners), (4, summary.corners));
+    assert!((rebuilt.draw_length - 900.0).abs() < 1e-3);
+    assert!((rebuilt.move_length - 300.0).abs() < 1e-3);
+    // straight strokes take as long as in the original
+    let original = strokes(4, 225.0);
+    let summary = original.summary();
+    assert_eq!((summary.pen_lifts, summary.corners), (4, [0; 6]));
+    let estimator = Estimator::default();
+    let a = estimator.estimate_toolpath(&original, 2000.0, 8000.0).as_secs_f32();
+    let b = estimator.estimate_toolpath(&Toolpath::from_summary(&summary), 2000.0, 8000.0);
+    assert!((a - b.as_secs_f32()).abs() < 0.1);
+}
+
+#[test]
+fn summaries_keep_corner_angles() {
+    // a circle of 1° turns, a square and a zigzag of right angles
+    let circle = (0..=360)
+        .map(|i: i32| (i as f32).to_radians())
+        .map(|a| Vec2::new(a.cos(), a.sin()) * 20.0)
+        .collect();
+    let square = [
+        (0.0, 0.0),
+        (30.0, 0.0),
+        (30.0, 30.0),
+        (0.0, 30.0),
+        (0.0, 0.0),
+    ];
+    let toolpath = Toolpath {
+        moves: vec![
+            Move { points: circle, pen_down: true },
+            Move {
+                points: square.map(|(x, y)| Vec2::new(x, y)).to_vec(),
+                pen_down: true,
+            },
+            zigzag(40, 200.0).moves.remove(0),
+        ],
+    };
+    let summary = toolpath.summary();
+    // nearly straight joints are no corners
+    assert_eq!(summary.corners, [0, 0, 0, 42, 0, 0]);
+    let estimator = Estimator { junction_deviation: 0.05, ..Estimator::default() };
+    let original = estimator.estimate_toolpath(&toolpath, 2000.0, 8000.0).as_secs_f32();
+    let rebuilt = Toolpath::from_summary(&summary);
+    let rebuilt = estimator.estimate_toolpath(&rebuilt, 2000.0, 8000.0).as_secs_f32();
+    assert!((original - rebuilt).abs() < 0.02 * original, "{original} vs {rebuilt}");
+}
+
+#[test]
+fn fit_recovers_parameters() {
+    let truth = Estimator {
+        acceleration: 1000.0,
+        junction_deviation: 0.03,
+        pen_lift: 0.4,
+        c_draw: 1.1,
+        c_move: 0.9,
+    };
+    let toolpaths = [
+        zigzag(500, 400.0),
+        zigzag(200, 100.0),
+        zigzag(50, 800.0),
+        zigzag(20, 60.0),
+        strokes(40, 20.0),
+        strokes(20, 3.0),
+        strokes(5, 150.0),
+        Toolpath::straight(2000.0, 500.0),
+    ];
+    let measurements: Vec<_> = toolpaths
+        .into_iter()
+        .map(|toolpath| {
+            let duration = truth.estimate_toolpath(&toolpath, 2000.0, 8000.0);
+            Measurement { toolpath, draw_speed: 2000.0, move_speed: 8000.0, duration }
+        })
+        .collect();
+    let fitted = fit_to(&measurements);
+    // the truth is off the search grid, the refinement has to find it
+    assert!((fitted.acceleration / truth.acceleration - 1.0).abs() < 0.1, "{fitted:?}");
+    assert!(
+        (fitted.junction_deviation / truth.junction_deviation - 1.0).abs() < 0.2,
+        "{fitted:?}"
+    );
+    assert!((fitted.pen_lift - truth.pen_lift).abs() < 0.01);
+    assert!((fitted.c_draw - truth.c_draw).abs() < 0.01);
+    assert!((fitted.c_move - truth.c_move).abs() < 0.01);
+}
+
+#[test]
+fn best_matches_legacy_plots() {
+    let estimate = Estimator::best().estimate_time(24901.33, 7490.3813, 2000.0, 8000.0);
+    assert!((estimate.as_secs_f32() - 480.0).abs() < 30.0, "{estimate:?}");
+    // the corrections already hold the pen lifts and corners of those plots,
+    // so splitting a plot into more polylines does not add to the estimate
+    let best = Estimator::best();
+    let (one, many) = (strokes(1, 1000.0), strokes(100, 10.0));
+    let difference = best.estimate_toolpath(&many, 2000.0, 8000.0).as_secs_f32()
+        - best.estimate_toolpath(&one, 2000.0, 8000.0).as_secs_f32();
+    assert!(difference.abs() < 1.0, "{difference}");
+}
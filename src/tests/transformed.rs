@@ -0,0 +1,38 @@
+use std::f32::consts::FRAC_PI_2;
+
+use nalgebra_glm::{quat_angle_axis, Vec2, Vec3};
+
+use crate::{
+    finite_difference::check_derivatives,
+    geometries::{sphere::Sphere, torus::Torus, transformed::Transformed},
+    geometry::Geometry,
+    sdf::SDF,
+};
+
+#[test]
+fn transformed_geometries() {
+    let tilt = quat_angle_axis(FRAC_PI_2, &Vec3::new(1.0, 0.0, 0.0));
+    let torus = Transformed::new(Torus::new(0.5, 2.0))
+        .with_rotation(&tilt)
+        .with_scale(Vec3::new(1.0, 2.0, 1.0))
+        .with_translation(Vec3::new(3.0, 0.0, -1.0));
+    // the outer equator at u = v = 0 is (2.5, 0, 0), the z axis turns into -y
+    let p = Vec2::new(0.0, FRAC_PI_2);
+    assert!((torus.evaluate(&Vec2::zeros()) - Vec3::new(5.5, 0.0, -1.0)).norm() < 1e-5);
+    assert!((torus.evaluate(&p) - Vec3::new(3.0, 0.0, 4.0)).norm() < 1e-5);
+    let mismatches = check_derivatives(&torus, Vec2::new(-2.0, -2.0), Vec2::new(2.0, 2.0), 5, 1e-2);
+    assert!(mismatches.is_empty(), "{mismatches:?}");
+    // points on the surface are on the zero level
+    for uv in [Vec2::zeros(), p, Vec2::new(1.0, -2.0)] {
+        assert!(torus.sdf(&torus.evaluate(&uv)).abs() < 1e-4);
+    }
+
+    // exact distances for a uniform scale, a lower bound otherwise
+    let ball = Transformed::new(Sphere).with_uniform_scale(2.0);
+    assert!((ball.sdf(&Vec3::new(0.0, 5.0, 0.0)) - 3.0).abs() < 1e-5);
+    let egg = Transformed::new(Sphere).with_scale(Vec3::new(1.0, 1.0, 3.0));
+    assert!((egg.sdf(&Vec3::new(2.0, 0.0, 0.0)) - 1.0).abs() < 1e-5);
+    let above = egg.sdf(&Vec3::new(0.0, 0.0, 5.0));
+    assert!(above > 0.0 && above <= 2.0);
+    assert!(egg.sdf(&Vec3::new(0.0, 0.0, 2.9)) < 0.0);
+}
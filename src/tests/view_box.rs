@@ -0,0 +1,33 @@
+use nalgebra_glm::Vec2;
+
+use crate::view_box::{inches, pixels, Margins, Orientation, ViewBox};
+
+#[test]
+fn presets_and_orientation() {
+    assert_eq!(ViewBox::A4.orientation(), Orientation::Portrait);
+    let landscape = ViewBox::A3.landscape();
+    assert_eq!((landscape.width, landscape.height), (420.0, 297.0));
+    assert_eq!(landscape.portrait(), ViewBox::A3);
+    // halving an A sheet gives the next size, rounded down
+    assert_eq!(ViewBox::A4.landscape().width / 2.0, ViewBox::A5.width + 0.5);
+    assert_eq!(ViewBox::from_inches(8.5, 11.0), ViewBox::LETTER);
+}
+
+#[test]
+fn asymmetric_margins() {
+    let margins = Margins { top: 10.0, right: 5.5, bottom: 20.0, left: 7.25 };
+    let area = ViewBox::A5.pad(margins);
+    assert_eq!(area, ViewBox::new(7.25, 10.0, 135.25, 180.0));
+    assert!(area.contains(&Vec2::new(8.0, 11.0)));
+    assert!(!area.contains(&Vec2::new(143.0, 100.0)));
+    assert_eq!(ViewBox::A5.pad(2.0), ViewBox::A5.pad(Margins::symmetric(2.0, 2.0)));
+}
+
+#[test]
+fn unit_conversions() {
+    assert_eq!(inches(2.0), 50.8);
+    assert!((pixels(300.0, 300.0) - 25.4).abs() < 1e-5);
+    let postcard = ViewBox::POSTCARD;
+    assert_eq!(postcard.to_pixels(300.0), (1200, 1800));
+    assert_eq!(ViewBox::from_pixels(1200.0, 1800.0, 300.0).to_pixels(300.0), (1200, 1800));
+}
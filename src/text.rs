@@ -0,0 +1,227 @@
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use nalgebra_glm::{rotate_vec2, Vec2};
+
+use crate::polyline::Polyline2;
+
+// single-stroke Roman Simplex in the Hershey `.jhf` format, ASCII 32 to 126
+const SIMPLEX: &str = include_str!("../data/fonts/simplex.jhf");
+
+// Hershey units from the top of capitals to the baseline
+const CAP_HEIGHT: f32 = 21.0;
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message.into())
+}
+
+// strokes in Hershey units, y pointing down with the baseline at 9
+struct Glyph {
+    left: f32,
+    right: f32,
+    strokes: Vec<Vec<Vec2>>,
+}
+
+/// A Hershey font. Glyphs are numbered from the space character in the
+/// order they appear in the file.
+pub struct Font {
+    glyphs: Vec<Glyph>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TextOptions {
+    // height of capitals in mm
+    pub size: f32,
+    // extra space between letters in mm
+    pub letter_spacing: f32,
+    // distance between baselines as a multiple of the size
+    pub line_spacing: f32,
+    pub align: Align,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self {
+            size: 5.0,
+            letter_spacing: 0.0,
+            line_spacing: 1.6,
+            align: Align::Left,
+        }
+    }
+}
+
+// one coordinate of a glyph, stored as the offset from 'R'
+fn coordinate(c: u8) -> f32 {
+    c as f32 - b'R' as f32
+}
+
+impl Font {
+    pub fn simplex() -> Font {
+        Font::parse(SIMPLEX).expect("embedded font is valid")
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Font> {
+        Font::parse(&fs::read_to_string(path)?)
+    }
+
+    // Each glyph is a 5 character id, a 3 character count of coordinate pairs
+    // and the pairs themselves. The first pair is the left and right bound
+    // and " R" lifts the pen. Long glyphs continue on the following lines.
+    pub fn parse(content: &str) -> io::Result<Font> {
+        let mut glyphs = Vec::new();
+        let mut lines = content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+        while let Some((index, line)) = lines.next() {
+            let line = line.as_bytes();
+            let count = std::str::from_utf8(line.get(5..8).unwrap_or_default())
+                .ok()
+                .and_then(|count| count.trim().parse::<usize>().ok())
+                .ok_or_else(|| invalid_data(format!("line {}: invalid glyph header", index + 1)))?;
+            let mut data = line[8..].to_vec();
+            while data.len() < 2 * count {
+                let (_, more) = lines.next().ok_or_else(|| {
+                    invalid_data(format!("line {}: glyph is cut short", index + 1))
+                })?;
+                data.extend_from_slice(more.as_bytes());
+            }
+            if count == 0 || data.len() != 2 * count {
+                return Err(invalid_data(format!("line {}: expected {count} pairs", index + 1)));
+            }
+
+            let mut glyph = Glyph {
+                left: coordinate(data[0]),
+                right: coordinate(data[1]),
+                strokes: vec![Vec::new()],
+            };
+            for pair in data[2..].chunks(2) {
+                if pair == b" R" {
+                    glyph.strokes.push(Vec::new());
+                } else {
+                    let point = Vec2::new(coordinate(pair[0]), coordinate(pair[1]));
+                    glyph.strokes.last_mut().unwrap().push(point);
+                }
+            }
+            glyph.strokes.retain(|stroke| !stroke.is_empty());
+            glyphs.push(glyph);
+        }
+        Ok(Font { glyphs })
+    }
+
+    // characters without a glyph are left out
+    fn glyph(&self, c: char) -> Option<&Glyph> {
+        (c as usize).checked_sub(32).and_then(|index| self.glyphs.get(index))
+    }
+
+    fn advance(&self, glyph: &Glyph, options: &TextOptions) -> f32 {
+        (glyph.right - glyph.left) * options.size / CAP_HEIGHT + options.letter_spacing
+    }
+
+    /// Width of one line of text in mm
+    pub fn width(&self, line: &str, options: &TextOptions) -> f32 {
+        let advances: f32 = line
+            .chars()
+            .filter_map(|c| self.glyph(c))
+            .map(|g| self.advance(g, options))
+            .sum();
+        // no spacing after the last letter
+        (advances - options.letter_spacing).max(0.0)
+    }
+
+    // strokes of a glyph with the left end of its baseline at the origin
+    fn strokes<'a>(
+        &self,
+        glyph: &'a Glyph,
+        options: &TextOptions,
+    ) -> impl Iterator<Item = Vec<Vec2>> + 'a {
+        let scale = options.size / CAP_HEIGHT;
+        let (left, baseline) = (glyph.left, 9.0);
+        glyph.strokes.iter().map(move |stroke| {
+            stroke.iter().map(|p| scale * Vec2::new(p.x - left, p.y - baseline)).collect()
+        })
+    }
+
+    /// Lays out the text with the baseline of the first line at `position`,
+    /// which is the left end, center or right end of the line depending on
+    /// the alignment. Lines are separated by `\n`.
+    pub fn layout(&self, text: &str, position: Vec2, options: &TextOptions) -> Vec<Polyline2> {
+        let mut polylines = Vec::new();
+        for (row, line) in text.lines().enumerate() {
+            let width = self.width(line, options);
+            let mut x = position.x
+                - match options.align {
+                    Align::Left => 0.0,
+                    Align::Center => 0.5 * width,
+                    Align::Right => width,
+                };
+            let y = position.y + row as f32 * options.line_spacing * options.size;
+            for glyph in line.chars().filter_map(|c| self.glyph(c)) {
+                let origin = Vec2::new(x, y);
+                for stroke in self.strokes(glyph, options) {
+                    polylines.push(stroke.iter().map(|p| p + origin).collect());
+                }
+                x += self.advance(glyph, options);
+            }
+        }
+        polylines
+    }
+
+    /// Lays out the text with its baseline following the path. Each letter is
+    /// placed upright on the path at the middle of its width, so letters are
+    /// rotated but not bent. The alignment places the text at the start,
+    /// middle or end of the path, and further lines go below the first.
+    pub fn along(&self, text: &str, path: &Polyline2, options: &TextOptions) -> Vec<Polyline2> {
+        let points: Vec<Vec2> = path.points_drawn().copied().collect();
+        let lengths: Vec<f32> = points.windows(2).map(|w| (w[1] - w[0]).norm()).collect();
+        let total: f32 = lengths.iter().sum();
+        if total <= 0.0 {
+            return Vec::new();
+        }
+        // point and direction at a distance along the path, extended straight
+        // beyond its ends
+        let at = |mut s: f32| -> (Vec2, Vec2) {
+            for (i, &length) in lengths.iter().enumerate() {
+                if length > 0.0 && (s <= length || i == lengths.len() - 1) {
+                    let direction = (points[i + 1] - points[i]) / length;
+                    return (points[i] + s * direction, direction);
+                }
+                s -= length;
+            }
+            let direction = (points[1] - points[0]).normalize();
+            (points[0] + s * direction, direction)
+        };
+
+        let mut polylines = Vec::new();
+        for (row, line) in text.lines().enumerate() {
+            let width = self.width(line, options);
+            let mut s = match options.align {
+                Align::Left => 0.0,
+                Align::Center => 0.5 * (total - width),
+                Align::Right => total - width,
+            };
+            let below = row as f32 * options.line_spacing * options.size;
+            for glyph in line.chars().filter_map(|c| self.glyph(c)) {
+                let half = 0.5 * (glyph.right - glyph.left) * options.size / CAP_HEIGHT;
+                let (point, direction) = at(s + half);
+                let angle = direction.y.atan2(direction.x);
+                for stroke in self.strokes(glyph, options) {
+                    let placed = stroke
+                        .iter()
+                        .map(|p| point + rotate_vec2(&Vec2::new(p.x - half, p.y + below), angle))
+                        .collect();
+                    polylines.push(placed);
+                }
+                s += self.advance(glyph, options);
+            }
+        }
+        polylines
+    }
+}
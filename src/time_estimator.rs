@@ -1,30 +1,291 @@
 use std::time::Duration;
 
+use nalgebra_glm::Vec2;
+
 use crate::paper::Paper;
 
-/// A tuple for one measurement:
-/// (draw_length, move_length, draw_speed, move_speed, duration)
-pub type Measurement = (f32, f32, f32, f32, Duration);
+/// One continuous motion, either drawing with the pen down or travelling
+/// with the pen up
+#[derive(Clone, Debug, PartialEq)]
+pub struct Move {
+    pub points: Vec<Vec2>,
+    pub pen_down: bool,
+}
+
+/// The moves a plotter makes, in order
+#[derive(Clone, Debug, PartialEq)]
+pub struct Toolpath {
+    pub moves: Vec<Move>,
+}
+
+/// Edges in degrees of the turning angle bins that `Summary` counts corners
+/// in. Joints turning less than the first edge are taken as straight.
+pub const CORNER_EDGES: [f32; 7] = [2.0, 10.0, 30.0, 60.0, 120.0, 150.0, 180.0];
+
+/// What matters of a toolpath for timing, small enough to store for every
+/// measured plot. Corners are only kept as a histogram of their turning
+/// angles, so a rebuilt toolpath turns by the middle of each bin.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Summary {
+    pub draw_length: f32,
+    pub move_length: f32,
+    pub pen_lifts: usize,
+    // joints between drawn segments by turning angle, see `CORNER_EDGES`
+    pub corners: [usize; CORNER_EDGES.len() - 1],
+}
+
+// the bin of `CORNER_EDGES` a turn between two directions falls in
+fn corner_bin(from: &Vec2, to: &Vec2) -> Option<usize> {
+    let angle = from.dot(to).clamp(-1.0, 1.0).acos().to_degrees();
+    if angle < CORNER_EDGES[0] {
+        return None;
+    }
+    let last = CORNER_EDGES.len() - 2;
+    Some(CORNER_EDGES[1..].iter().position(|&edge| angle < edge).unwrap_or(last))
+}
+
+impl Toolpath {
+    // every layer starts at home, like `Paper::length`
+    pub fn from_paper(paper: &Paper) -> Toolpath {
+        let mut moves = Vec::new();
+        for layer in paper.layers() {
+            let mut pen = paper.home();
+            for polyline in layer.polylines() {
+                let Some(&start) = polyline.points.first() else {
+                    continue;
+                };
+                moves.push(Move { points: vec![pen, start], pen_down: false });
+                moves.push(Move {
+                    points: polyline.points_drawn().copied().collect(),
+                    pen_down: true,
+                });
+                pen = *polyline.end().unwrap_or(&pen);
+            }
+        }
+        Toolpath { moves }
+    }
+
+    // a single straight stroke and travel, for when only lengths are known
+    pub fn straight(draw_length: f32, move_length: f32) -> Toolpath {
+        let line = |length: f32, pen_down| Move {
+            points: vec![Vec2::zeros(), Vec2::new(length, 0.0)],
+            pen_down,
+        };
+        Toolpath {
+            moves: vec![line(draw_length, true), line(move_length, false)],
+        }
+    }
 
+    // A toolpath with the lengths, pen lifts and corners of the summary. Every
+    // lift draws a stroke of the same length after a straight move, and the
+    // corners are dealt out over the strokes, turning by the middle of their
+    // bin alternately left and right.
+    pub fn from_summary(summary: &Summary) -> Toolpath {
+        let strokes = summary.pen_lifts;
+        if strokes == 0 {
+            let points = vec![Vec2::zeros(), Vec2::new(summary.move_length, 0.0)];
+            return Toolpath { moves: vec![Move { points, pen_down: false }] };
+        }
+        let draw_length = summary.draw_length / strokes as f32;
+        let move_length = summary.move_length / strokes as f32;
+        let angles: Vec<f32> = (0..summary.corners.len())
+            .flat_map(|bin| {
+                let angle = 0.5 * (CORNER_EDGES[bin] + CORNER_EDGES[bin + 1]);
+                std::iter::repeat_n(angle.to_radians(), summary.corners[bin])
+            })
+            .collect();
+        let mut moves = Vec::with_capacity(2 * strokes);
+        for i in 0..strokes {
+            let turns: Vec<f32> = angles.iter().skip(i).step_by(strokes).copied().collect();
+            let step = draw_length / (turns.len() + 1) as f32;
+            let mut point = Vec2::new(move_length, 0.0);
+            moves.push(Move { points: vec![Vec2::zeros(), point], pen_down: false });
+            let mut points = vec![point];
+            let mut heading = 0.0_f32;
+            for k in 0..=turns.len() {
+                point += Vec2::new(heading.cos(), heading.sin()) * step;
+                points.push(point);
+                if let Some(turn) = turns.get(k) {
+                    heading += if k % 2 == 0 { *turn } else { -turn };
+                }
+            }
+            moves.push(Move { points, pen_down: true });
+        }
+        Toolpath { moves }
+    }
+
+    pub fn summary(&self) -> Summary {
+        let mut summary = Summary { pen_lifts: self.pen_lifts(), ..Summary::default() };
+        for m in &self.moves {
+            let segments: Vec<Vec2> =
+                m.points.windows(2).map(|w| w[1] - w[0]).filter(|d| d.norm() > 0.0).collect();
+            let length = segments.iter().map(|d| d.norm()).sum::<f32>();
+            if m.pen_down {
+                summary.draw_length += length;
+                for w in segments.windows(2) {
+                    if let Some(bin) = corner_bin(&w[0].normalize(), &w[1].normalize()) {
+                        summary.corners[bin] += 1;
+                    }
+                }
+            } else {
+                summary.move_length += length;
+            }
+        }
+        summary
+    }
+
+    pub fn pen_lifts(&self) -> usize {
+        self.moves.iter().filter(|m| m.pen_down).count()
+    }
+}
+
+/// A plot that was timed on the machine, speeds in mm/min
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measurement {
+    pub toolpath: Toolpath,
+    pub draw_speed: f32,
+    pub move_speed: f32,
+    pub duration: Duration,
+}
+
+/// Motion model of a GRBL style planner. Every move starts and ends at rest,
+/// segments follow trapezoidal velocity profiles and corners are taken at
+/// the junction deviation speed limit.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Estimator {
-    c_draw: f32,
-    c_move: f32,
+    // mm/s²
+    pub acceleration: f32,
+    // mm, GRBL setting $11
+    pub junction_deviation: f32,
+    // seconds per pen down and up
+    pub pen_lift: f32,
+    // corrections for the time spent drawing and moving
+    pub c_draw: f32,
+    pub c_move: f32,
+}
+
+impl Default for Estimator {
+    fn default() -> Self {
+        Self {
+            acceleration: 400.0,
+            junction_deviation: 0.01,
+            pen_lift: 0.3,
+            c_draw: 1.0,
+            c_move: 1.0,
+        }
+    }
+}
+
+// time for one segment of `length` entered at speed² `u2` and left at `w2`
+fn segment_time(length: f32, u2: f32, w2: f32, speed: f32, acceleration: f32) -> f32 {
+    let (u, w) = (u2.sqrt(), w2.sqrt());
+    let accelerating = (speed * speed - u2) / (2.0 * acceleration);
+    let decelerating = (speed * speed - w2) / (2.0 * acceleration);
+    if accelerating + decelerating <= length {
+        (speed - u) / acceleration
+            + (speed - w) / acceleration
+            + (length - accelerating - decelerating) / speed
+    } else {
+        // triangle profile, never reaches the feed rate
+        let peak = ((2.0 * acceleration * length + u2 + w2) / 2.0).sqrt();
+        (peak - u) / acceleration + (peak - w) / acceleration
+    }
 }
 
 impl Estimator {
+    // calibrated on plots of the iDraw pen plotter. Only the lengths of those
+    // plots were recorded, so their pen lifts and corners are in the fitted
+    // corrections. The profile has no pen lift time and no acceleration
+    // limit to not count them twice.
     pub fn best() -> Self {
-        let measurements = vec![
-            (24901.33, 7490.3813, 2000.0, 8000.0, Duration::from_secs(8 * 60)),
-            (20193.04, 5596.8086, 2000.0, 8000.0, Duration::from_secs(6 * 60 + 18)),
-            (11244.227, 2893.787, 2000.0, 8000.0, Duration::from_secs(3 * 60 + 52)),
-            (19574.28, 6561.491, 2000.0, 8000.0, Duration::from_secs(6 * 60 + 13)),
-        ];
-        fit_to(&measurements)
+        let measurements: Vec<_> = [
+            (24901.33, 7490.3813, Duration::from_secs(8 * 60)),
+            (20193.04, 5596.8086, Duration::from_secs(6 * 60 + 18)),
+            (11244.227, 2893.787, Duration::from_secs(3 * 60 + 52)),
+            (19574.28, 6561.491, Duration::from_secs(6 * 60 + 13)),
+        ]
+        .into_iter()
+        .map(|(draw_length, move_length, duration)| Measurement {
+            toolpath: Toolpath::straight(draw_length, move_length),
+            draw_speed: 2000.0,
+            move_speed: 8000.0,
+            duration,
+        })
+        .collect();
+        let legacy = Estimator {
+            acceleration: f32::INFINITY,
+            pen_lift: 0.0,
+            ..Estimator::default()
+        };
+        fit_linear(&measurements, legacy, false).0
+    }
+
+    // time for a chain of segments at `speed` mm/s, starting and ending at rest
+    fn chain_time(&self, points: &[Vec2], speed: f32) -> f32 {
+        let segments: Vec<(f32, Vec2)> = points
+            .windows(2)
+            .map(|w| w[1] - w[0])
+            .filter(|d| d.norm() > 0.0)
+            .map(|d| (d.norm(), d / d.norm()))
+            .collect();
+        let n = segments.len();
+        if n == 0 || speed <= 0.0 {
+            return 0.0;
+        }
+        let a = self.acceleration;
+        // squared speed limits at the start of each segment and at the end
+        let mut limits = vec![speed * speed; n + 1];
+        limits[0] = 0.0;
+        limits[n] = 0.0;
+        for j in 1..n {
+            let cos_theta = -segments[j - 1].1.dot(&segments[j].1);
+            if cos_theta > -0.999999 {
+                let sin_half = (0.5 * (1.0 - cos_theta)).max(0.0).sqrt();
+                let junction = a * self.junction_deviation * sin_half / (1.0 - sin_half).max(1e-6);
+                limits[j] = limits[j].min(junction);
+            }
+        }
+        // reachable speeds given the acceleration, backwards then forwards
+        for i in (0..n).rev() {
+            limits[i] = limits[i].min(limits[i + 1] + 2.0 * a * segments[i].0);
+        }
+        for i in 0..n {
+            limits[i + 1] = limits[i + 1].min(limits[i] + 2.0 * a * segments[i].0);
+        }
+        (0..n)
+            .map(|i| segment_time(segments[i].0, limits[i], limits[i + 1], speed, a))
+            .sum()
+    }
+
+    // drawing seconds, moving seconds and pen lifts before the corrections
+    fn parts(&self, toolpath: &Toolpath, draw_speed: f32, move_speed: f32) -> (f32, f32, f32) {
+        let (mut draw, mut travel) = (0.0, 0.0);
+        for m in &toolpath.moves {
+            if m.pen_down {
+                draw += self.chain_time(&m.points, draw_speed / 60.0);
+            } else {
+                travel += self.chain_time(&m.points, move_speed / 60.0);
+            }
+        }
+        (draw, travel, toolpath.pen_lifts() as f32)
     }
+
+    pub fn estimate_toolpath(
+        &self,
+        toolpath: &Toolpath,
+        draw_speed: f32,
+        move_speed: f32,
+    ) -> Duration {
+        let (draw, travel, lifts) = self.parts(toolpath, draw_speed, move_speed);
+        let seconds = self.c_draw * draw + self.c_move * travel + self.pen_lift * lifts;
+        Duration::from_secs_f32(seconds.max(0.0))
+    }
+
+    // speeds in mm/min, like the G-code feed rates
     pub fn estimate(&self, paper: &Paper, draw_speed: f32, move_speed: f32) -> Duration {
-        let (draw_length, move_length) = paper.length();
-        self.estimate_time(draw_length, move_length, draw_speed, move_speed)
+        self.estimate_toolpath(&Toolpath::from_paper(paper), draw_speed, move_speed)
     }
+
     pub fn estimate_time(
         &self,
         draw_length: f32,
@@ -32,30 +293,153 @@ impl Estimator {
         draw_speed: f32,
         move_speed: f32,
     ) -> Duration {
-        let draw_time = (draw_length / draw_speed) * self.c_draw;
-        let move_time = (move_length / move_speed) * self.c_move;
-        Duration::from_secs_f32(draw_time + move_time)
+        let toolpath = Toolpath::straight(draw_length, move_length);
+        self.estimate_toolpath(&toolpath, draw_speed, move_speed)
     }
 }
 
-pub fn fit_to(measurements: &[Measurement]) -> Estimator {
-    let (mut sum_d2, mut sum_dm, mut sum_m2, mut sum_dt, mut sum_mt) = (0.0, 0.0, 0.0, 0.0, 0.0);
+// Cramer's rule, None if the system is singular
+fn solve3(a: [[f64; 3]; 3], b: [f64; 3]) -> Option<[f64; 3]> {
+    let det = |m: [[f64; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let d = det(a);
+    if d.abs() < 1e-12 {
+        return None;
+    }
+    let mut x = [0.0; 3];
+    for (column, value) in x.iter_mut().enumerate() {
+        let mut m = a;
+        for row in 0..3 {
+            m[row][column] = b[row];
+        }
+        *value = det(m) / d;
+    }
+    Some(x)
+}
 
-    for &(draw_len, move_len, draw_spd, move_spd, duration) in measurements {
-        let d = draw_len / draw_spd;
-        let m = move_len / move_spd;
-        let t = duration.as_secs_f32();
+// Least squares for the drawing and moving corrections and the pen lift
+// time with the motion parameters of `model` fixed. Without `fit_pen_lift`
+// the pen lift time of the model is kept. Returns the estimator and its sum
+// of squared errors.
+fn fit_linear(
+    measurements: &[Measurement],
+    model: Estimator,
+    fit_pen_lift: bool,
+) -> (Estimator, f64) {
+    let rows: Vec<([f64; 3], f64)> = measurements
+        .iter()
+        .map(|m| {
+            let (draw, travel, lifts) = model.parts(&m.toolpath, m.draw_speed, m.move_speed);
+            let t = m.duration.as_secs_f64();
+            if fit_pen_lift {
+                ([draw as f64, travel as f64, lifts as f64], t)
+            } else {
+                ([draw as f64, travel as f64, 0.0], t - (model.pen_lift * lifts) as f64)
+            }
+        })
+        .collect();
 
-        sum_d2 += d * d;
-        sum_dm += d * m;
-        sum_m2 += m * m;
-        sum_dt += d * t;
-        sum_mt += m * t;
+    let mut normal = [[0.0; 3]; 3];
+    let mut rhs = [0.0; 3];
+    for (x, t) in &rows {
+        for i in 0..3 {
+            for j in 0..3 {
+                normal[i][j] += x[i] * x[j];
+            }
+            rhs[i] += x[i] * t;
+        }
+    }
+    // a little ridge keeps parameters the measurements cannot tell apart
+    // near zero instead of failing
+    let trace = normal[0][0] + normal[1][1] + normal[2][2];
+    for (i, row) in normal.iter_mut().enumerate() {
+        row[i] += 1e-9 * trace + 1e-12;
     }
+    let Some([c_draw, c_move, pen_lift]) = solve3(normal, rhs) else {
+        return (model, f64::INFINITY);
+    };
 
-    let det = sum_d2 * sum_m2 - sum_dm * sum_dm;
-    let c_draw = (sum_m2 * sum_dt - sum_dm * sum_mt) / det;
-    let c_move = (sum_d2 * sum_mt - sum_dm * sum_dt) / det;
+    let error = rows
+        .iter()
+        .map(|(x, t)| (c_draw * x[0] + c_move * x[1] + pen_lift * x[2] - t).powi(2))
+        .sum();
+    let fitted = Estimator {
+        c_draw: c_draw as f32,
+        c_move: c_move as f32,
+        pen_lift: if fit_pen_lift {
+            pen_lift as f32
+        } else {
+            model.pen_lift
+        },
+        ..model
+    };
+    (fitted, error)
+}
+
+/// Calibrates the estimator from timed plots. Acceleration and junction
+/// deviation are found by a grid search refined by a pattern search, the
+/// remaining parameters are linear and solved by least squares for every
+/// candidate.
+pub fn fit_to(measurements: &[Measurement]) -> Estimator {
+    const ACCELERATIONS: [f32; 8] = [50.0, 100.0, 200.0, 400.0, 800.0, 1600.0, 3200.0, 6400.0];
+    const JUNCTION_DEVIATIONS: [f32; 6] = [0.002, 0.005, 0.01, 0.02, 0.05, 0.1];
+    // with the same number of pen lifts in every plot their time cannot be
+    // told apart from a constant overhead
+    let fit_pen_lift = measurements
+        .windows(2)
+        .any(|w| w[0].toolpath.pen_lifts() != w[1].toolpath.pen_lifts());
+    let fit = |acceleration, junction_deviation| {
+        let model = Estimator { acceleration, junction_deviation, ..Estimator::default() };
+        fit_linear(measurements, model, fit_pen_lift)
+    };
+    let mut best = (Estimator::default(), f64::INFINITY);
+    for acceleration in ACCELERATIONS {
+        for junction_deviation in JUNCTION_DEVIATIONS {
+            let fitted = fit(acceleration, junction_deviation);
+            if fitted.1 < best.1 {
+                best = fitted;
+            }
+        }
+    }
+    // scale the parameters up or down while that helps, then take smaller
+    // steps, until they are within 0.1%
+    let mut factor = 2.0_f32;
+    while factor > 1.001 {
+        let (acceleration, junction_deviation) = (best.0.acceleration, best.0.junction_deviation);
+        let candidates = [
+            (acceleration * factor, junction_deviation),
+            (acceleration / factor, junction_deviation),
+            (acceleration, junction_deviation * factor),
+            (acceleration, junction_deviation / factor),
+            // corner speeds depend on their product, so trading one for the
+            // other follows the valley of the error
+            (acceleration * factor, junction_deviation / factor),
+            (acceleration / factor, junction_deviation * factor),
+        ];
+        let mut improved = false;
+        for (acceleration, junction_deviation) in candidates {
+            let fitted = fit(acceleration, junction_deviation);
+            if fitted.1 < best.1 {
+                (best, improved) = (fitted, true);
+            }
+        }
+        if !improved {
+            factor = factor.sqrt();
+        }
+    }
+    best.0
+}
 
-    Estimator { c_draw, c_move }
+/// Estimated minus measured seconds for every measurement
+pub fn residuals(estimator: &Estimator, measurements: &[Measurement]) -> Vec<f32> {
+    measurements
+        .iter()
+        .map(|m| {
+            let estimate = estimator.estimate_toolpath(&m.toolpath, m.draw_speed, m.move_speed);
+            estimate.as_secs_f32() - m.duration.as_secs_f32()
+        })
+        .collect()
 }
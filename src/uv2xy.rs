@@ -2,12 +2,13 @@ use nalgebra_glm::{distance, Vec3, Vec4};
 
 use crate::{
     camera::Camera,
+    clip::clip_to_view_box,
     eq::NewtonRaphsonOptions,
     geometry::Geometry,
-    paper::ViewBox,
     polyline::{Polyline2, Polyline4},
     raytracer::{backproject, Tracer},
     sdf::SDF,
+    view_box::ViewBox,
 };
 
 fn in_front_of_camera(screen: &Vec3) -> bool {
@@ -36,11 +37,12 @@ fn visible(
 // 2. Project using camera
 // 3. Drop points behind the camera
 // 4. Handle occlusion
+// 5. Clip to the area
 pub fn reproject<G: Geometry + SDF>(
     polyline: &Polyline2,
     geometry: &G,
     camera: &Camera,
-    _area: ViewBox,
+    area: ViewBox,
     near: f32,
     far: f32,
 ) -> Vec<Polyline4> {
@@ -74,7 +76,7 @@ pub fn reproject<G: Geometry + SDF>(
         }
     }
     polylines.push(current);
-    polylines
+    polylines.iter().flat_map(|polyline| clip_to_view_box(polyline, area)).collect()
 }
 
 pub fn keep_xy(polylines: Vec<Polyline4>) -> Vec<Polyline2> {
@@ -0,0 +1,186 @@
+use nalgebra_glm::{Vec2, Vec4};
+
+pub const MM_PER_INCH: f32 = 25.4;
+
+// converts inches to millimetres
+pub fn inches(value: f32) -> f32 {
+    value * MM_PER_INCH
+}
+
+// converts pixels at the given resolution to millimetres
+pub fn pixels(value: f32, dpi: f32) -> f32 {
+    value * MM_PER_INCH / dpi
+}
+
+// converts millimetres to pixels at the given resolution
+pub fn to_pixels(millimetres: f32, dpi: f32) -> f32 {
+    millimetres * dpi / MM_PER_INCH
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+/// Space left free on each side of a view box, in millimetres
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Margins {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl Margins {
+    pub fn uniform(margin: f32) -> Margins {
+        Margins { top: margin, right: margin, bottom: margin, left: margin }
+    }
+
+    pub fn symmetric(vertical: f32, horizontal: f32) -> Margins {
+        Margins {
+            top: vertical,
+            right: horizontal,
+            bottom: vertical,
+            left: horizontal,
+        }
+    }
+}
+
+impl From<f32> for Margins {
+    fn from(margin: f32) -> Self {
+        Margins::uniform(margin)
+    }
+}
+
+/// A rectangle in millimetres. Presets are in portrait orientation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewBox {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl ViewBox {
+    // ISO 216 A series
+    pub const A0: ViewBox = ViewBox::size(841.0, 1189.0);
+    pub const A1: ViewBox = ViewBox::size(594.0, 841.0);
+    pub const A2: ViewBox = ViewBox::size(420.0, 594.0);
+    pub const A3: ViewBox = ViewBox::size(297.0, 420.0);
+    pub const A4: ViewBox = ViewBox::size(210.0, 297.0);
+    pub const A5: ViewBox = ViewBox::size(148.0, 210.0);
+    pub const A6: ViewBox = ViewBox::size(105.0, 148.0);
+    // ISO 216 B series
+    pub const B0: ViewBox = ViewBox::size(1000.0, 1414.0);
+    pub const B1: ViewBox = ViewBox::size(707.0, 1000.0);
+    pub const B2: ViewBox = ViewBox::size(500.0, 707.0);
+    pub const B3: ViewBox = ViewBox::size(353.0, 500.0);
+    pub const B4: ViewBox = ViewBox::size(250.0, 353.0);
+    pub const B5: ViewBox = ViewBox::size(176.0, 250.0);
+    pub const B6: ViewBox = ViewBox::size(125.0, 176.0);
+    // US sizes
+    pub const LETTER: ViewBox = ViewBox::size(215.9, 279.4);
+    pub const LEGAL: ViewBox = ViewBox::size(215.9, 355.6);
+    pub const TABLOID: ViewBox = ViewBox::size(279.4, 431.8);
+    // 4 x 6 inch
+    pub const POSTCARD: ViewBox = ViewBox::size(101.6, 152.4);
+
+    pub const fn new(x: f32, y: f32, width: f32, height: f32) -> ViewBox {
+        ViewBox { x, y, width, height }
+    }
+
+    // a sheet with its corner at the origin
+    pub const fn size(width: f32, height: f32) -> ViewBox {
+        ViewBox::new(0.0, 0.0, width, height)
+    }
+
+    pub fn from_inches(width: f32, height: f32) -> ViewBox {
+        ViewBox::size(inches(width), inches(height))
+    }
+
+    pub fn from_pixels(width: f32, height: f32, dpi: f32) -> ViewBox {
+        ViewBox::size(pixels(width, dpi), pixels(height, dpi))
+    }
+
+    pub fn right(&self) -> f32 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.y + self.height
+    }
+
+    pub fn aspect(&self) -> f32 {
+        self.width / self.height
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        if self.width > self.height {
+            Orientation::Landscape
+        } else {
+            Orientation::Portrait
+        }
+    }
+
+    // swaps width and height if needed
+    pub fn with_orientation(self, orientation: Orientation) -> ViewBox {
+        if self.orientation() == orientation {
+            self
+        } else {
+            ViewBox::new(self.x, self.y, self.height, self.width)
+        }
+    }
+
+    pub fn portrait(self) -> ViewBox {
+        self.with_orientation(Orientation::Portrait)
+    }
+
+    pub fn landscape(self) -> ViewBox {
+        self.with_orientation(Orientation::Landscape)
+    }
+
+    // shrinks the box by the margins, e.g. `view_box.pad(8.0)`
+    pub fn pad(&self, margins: impl Into<Margins>) -> ViewBox {
+        let m = margins.into();
+        ViewBox::new(
+            self.x + m.left,
+            self.y + m.top,
+            self.width - m.left - m.right,
+            self.height - m.top - m.bottom,
+        )
+    }
+
+    // smallest box containing all points, None without points
+    pub fn bounding<'a>(points: impl IntoIterator<Item = &'a Vec2>) -> Option<ViewBox> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let (min, max) = points.fold((*first, *first), |(min, max), p| (min.inf(p), max.sup(p)));
+        Some(ViewBox::new(min.x, min.y, max.x - min.x, max.y - min.y))
+    }
+
+    // smallest box containing both
+    pub fn union(&self, other: &ViewBox) -> ViewBox {
+        let (x, y) = (self.x.min(other.x), self.y.min(other.y));
+        let (right, bottom) = (self.right().max(other.right()), self.bottom().max(other.bottom()));
+        ViewBox::new(x, y, right - x, bottom - y)
+    }
+
+    pub fn center(&self) -> Vec2 {
+        Vec2::new(self.x + 0.5 * self.width, self.y + 0.5 * self.height)
+    }
+
+    pub fn contains(&self, point: &Vec2) -> bool {
+        point.x > self.x && point.y > self.y && point.x < self.right() && point.y < self.bottom()
+    }
+
+    // camera viewport covering the box
+    pub fn viewport(&self) -> Vec4 {
+        Vec4::new(self.x, self.y, self.width, self.height)
+    }
+
+    pub fn to_pixels(&self, dpi: f32) -> (u32, u32) {
+        let (w, h) = (to_pixels(self.width, dpi), to_pixels(self.height, dpi));
+        (w.round() as u32, h.round() as u32)
+    }
+}